@@ -7,9 +7,11 @@
   };
 
   outputs = { self, nixpkgs, utils }:
-    utils.lib.eachSystem [ "x86_64-linux" ] (system:
+    let
+      sources = builtins.fromJSON (builtins.readFile ./sources.json);
+    in
+    utils.lib.eachSystem (builtins.attrNames sources) (system:
       let
-        sources = builtins.fromJSON (builtins.readFile ./sources.json);
         buildAdoptLike = with import nixpkgs { system = system; }; name: value:
           let
             cpuName = stdenv.hostPlatform.parsed.cpu.name;
@@ -81,35 +83,70 @@
                 patchelf --add-needed libfontconfig.so {} \;
             '';
           };
+        buildAdoptLikeDarwin = with import nixpkgs { system = system; }; name: value:
+          let
+            javaHome = "$out/${value.java_home or ""}";
+          in
+          stdenv.mkDerivation rec {
+            name = "jdk${toString value.major_version}";
+            src = builtins.fetchurl {
+              url = value.link;
+              sha256 = value.sha256;
+            };
+            version = value.java_version;
+            dontStrip = 1;
+            installPhase = ''
+              cd ..
+              mv $sourceRoot $out
+              # jni.h expects jni_md.h to be in the header search path.
+              ln -s ${javaHome}/include/darwin/*_md.h ${javaHome}/include/
+              rm -rf ${javaHome}/demo
+              # Remove some broken manpages.
+              rm -rf ${javaHome}/man/ja*
+            '' + lib.optionalString (value ? java_home) ''
+              # Expose the JDK root at the top level, like on linux
+              ln -s ${javaHome}/* $out/
+            '' + ''
+              # Propagate the setJavaClassPath setup hook from the JDK so that
+              # any package that depends on the JDK has $CLASSPATH set up
+              # properly.
+              mkdir -p $out/nix-support
+              printWords ${setJavaClassPath} > $out/nix-support/propagated-build-inputs
+              # Set JAVA_HOME automatically.
+              cat <<EOF >> "$out/nix-support/setup-hook"
+              if [ -z "\''${JAVA_HOME-}" ]; then export JAVA_HOME=${javaHome}; fi
+              EOF
+            '';
+          };
+        buildJdk =
+          if (import nixpkgs { system = system; }).stdenv.isDarwin
+          then buildAdoptLikeDarwin
+          else buildAdoptLike;
+        # Builds all the versions and channels of a vendor for this system
+        buildVendor = vendor:
+          let
+            vendorSources = sources.${system}.${vendor};
+          in
+          (builtins.mapAttrs
+            (name: value:
+              buildJdk name value)
+            vendorSources.versions) // {
+            latest = buildJdk "latest" vendorSources.latest;
+            stable = buildJdk "stable" vendorSources.stable;
+            lts = buildJdk "lts" vendorSources.lts;
+          };
       in
       with import nixpkgs { system = system; };
       {
-        packages.temurin = (builtins.mapAttrs
-          (name: value:
-            buildAdoptLike name value
-          )
-          sources.${system}.temurin.versions) // {
-          latest = buildAdoptLike "latest" sources.${system}.temurin.latest;
-          stable = buildAdoptLike "stable" sources.${system}.temurin.stable;
-          lts = buildAdoptLike "lts" sources.${system}.temurin.lts;
-        };
-
-        packages.temurin-latest = self.packages.${system}.temurin.latest;
-        packages.temurin-stable = self.packages.${system}.temurin.stable;
-        packages.temurin-lts = self.packages.${system}.temurin.lts;
-
-        packages.semeru = (builtins.mapAttrs
-          (name: value:
-            buildAdoptLike name value)
-          sources.${system}.semeru.versions) // {
-          latest = buildAdoptLike "latest" sources.${system}.semeru.latest;
-          stable = buildAdoptLike "stable" sources.${system}.semeru.stable;
-          lts = buildAdoptLike "lts" sources.${system}.semeru.lts;
-        };
-
-        packages.semeru-latest = self.packages.${system}.semeru.latest;
-        packages.semeru-stable = self.packages.${system}.semeru.stable;
-        packages.semeru-lts = self.packages.${system}.semeru.lts;
+        packages = lib.foldl'
+          (packages: vendor: packages // {
+            ${vendor} = buildVendor vendor;
+            "${vendor}-latest" = self.packages.${system}.${vendor}.latest;
+            "${vendor}-stable" = self.packages.${system}.${vendor}.stable;
+            "${vendor}-lts" = self.packages.${system}.${vendor}.lts;
+          })
+          { }
+          (builtins.attrNames sources.${system});
 
         defaultPackage = self.packages.${system}.stable;
       });
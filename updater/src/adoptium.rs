@@ -1,15 +1,46 @@
 use std::collections::BTreeMap;
 
+use async_lock::OnceCell;
+use async_trait::async_trait;
 use color_eyre::{
-    eyre::{eyre, Context, Result},
+    eyre::{eyre, Context as _, Result},
     Help, SectionExt,
 };
+use futures::{future::try_join_all, FutureExt};
 use serde::{Deserialize, Serialize};
-use surf::Client;
+
+use crate::{
+    config::{api_url, Config},
+    context::Context,
+    pager::{self, NotFound},
+    platform::{Os, Platform},
+    prefetch::PublishedChecksum,
+    retry,
+    vendor::{self, Channels, Vendor},
+};
 
 /// Page size
 pub const PAGE_SIZE: u64 = 10;
 
+/// Name of an operating system in the adoptium api, which calls macos `mac`
+///
+/// Also used by the marketplace api and semeru's archive names.
+pub fn os_name(os: Os) -> &'static str {
+    match os {
+        Os::Linux => "linux",
+        Os::Darwin => "mac",
+    }
+}
+
+/// Looks up an operating system by its name in the adoptium api
+fn os_from_name(name: &str) -> Option<Os> {
+    match name {
+        "linux" => Some(Os::Linux),
+        "mac" => Some(Os::Darwin),
+        _ => None,
+    }
+}
+
 /// Response from `/v3/info/available_releases` endpoint
 #[derive(Deserialize, Serialize, Debug)]
 pub struct AvailableReleases {
@@ -31,6 +62,17 @@ pub struct Package {
     size: u64,
 }
 
+impl Package {
+    /// The checksums adoptium published for this package
+    pub fn published_checksum(&self) -> PublishedChecksum {
+        let non_empty = |s: &String| Some(s.clone()).filter(|s| !s.is_empty());
+        PublishedChecksum {
+            checksum: non_empty(&self.checksum),
+            checksum_link: non_empty(&self.checksum_link),
+        }
+    }
+}
+
 /// Information about a particular binary
 #[derive(Deserialize, Serialize, Debug)]
 pub struct Binary {
@@ -39,7 +81,7 @@ pub struct Binary {
     heap_size: String,
     image_type: String,
     jvm_impl: String,
-    os: String,
+    pub os: String,
     pub package: Package,
     project: String,
     updated_at: String,
@@ -66,25 +108,17 @@ pub struct VersionData {
 
 impl PartialOrd for VersionData {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        match self.major.partial_cmp(&other.major) {
-            Some(std::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.minor.partial_cmp(&other.minor) {
-            Some(std::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        match self.security.partial_cmp(&other.security) {
-            Some(std::cmp::Ordering::Equal) => {}
-            ord => return ord,
-        }
-        self.build.partial_cmp(&other.build)
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for VersionData {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
+        self.major
+            .cmp(&other.major)
+            .then(self.minor.cmp(&other.minor))
+            .then(self.security.cmp(&other.security))
+            .then(self.build.cmp(&other.build))
     }
 }
 
@@ -103,6 +137,27 @@ pub struct Release {
     pub version_data: VersionData,
 }
 
+impl Release {
+    /// Converts into a package of the vendor agnostic pipeline
+    pub fn into_package(self) -> Result<vendor::Package> {
+        let [binary]: [Binary; 1] = self
+            .binaries
+            .try_into()
+            .map_err(|_| eyre!("Adoptium release had an incorrect number of binaries"))?;
+        Ok(vendor::Package {
+            checksum: binary.package.published_checksum(),
+            link: binary.package.link,
+            major_version: self.version_data.major,
+            java_version: self.version_data.openjdk_version,
+            early_access: self.release_type == "ea",
+            java_home: os_from_name(&binary.os)
+                .and_then(Os::java_home)
+                .map(str::to_string),
+            distribution_version: None,
+        })
+    }
+}
+
 impl PartialEq for Release {
     fn eq(&self, other: &Self) -> bool {
         self.version_data == other.version_data
@@ -113,7 +168,7 @@ impl Eq for Release {}
 
 impl PartialOrd for Release {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        self.version_data.partial_cmp(&other.version_data)
+        Some(self.cmp(other))
     }
 }
 
@@ -123,18 +178,6 @@ impl Ord for Release {
     }
 }
 
-/// Attempts to get the available releases
-pub async fn get_available_releases(client: &Client) -> Result<AvailableReleases> {
-    let endpoint = "https://api.adoptium.net/v3/info/available_releases";
-    client
-        .get(endpoint)
-        .recv_json()
-        .await
-        .map_err(|e| eyre!(e))
-        .context("Failed to request available versions from adoptium")
-        .with_section(|| endpoint.to_string().header("Failed Request:"))
-}
-
 /// Release query struct
 #[derive(Deserialize, Serialize, Debug)]
 pub struct ReleaseQuery {
@@ -143,74 +186,180 @@ pub struct ReleaseQuery {
     pub image_type: String,
     pub jvm_impl: String,
     pub os: String,
+    pub page: u64,
     pub page_size: u64,
     pub project: String,
+    pub sort_method: String,
+    pub sort_order: String,
 }
 
-/// Attempts to get the release info for a particular version
-pub async fn get_release(client: &Client, version: u64, release_type: &str) -> Result<Release> {
-    let endpoint = format!(
-        "https://api.adoptium.net/v3/assets/feature_releases/{}/{}",
-        version, release_type
-    );
-    let request = client
-        .get(endpoint)
-        .query(&ReleaseQuery {
-            architecture: "x64".to_string(),
-            heap_size: "normal".to_string(),
-            image_type: "jdk".to_string(),
-            os: "linux".to_string(),
-            page_size: PAGE_SIZE,
-            project: "jdk".to_string(),
-            jvm_impl: "hotspot".to_string(),
-        })
-        .map_err(|e| eyre!(e))
-        .context("Failed to build request")?
-        .build();
-    let query = request.url().as_str().to_string();
-    let mut releases: Vec<Release> = client
-        .recv_json(request)
-        .await
-        .map_err(|e| eyre!(e))
-        .context("Failed to get release information from adoptium")
-        .with_section(move || query.header("Failed Request"))?;
-    releases.sort();
-    match releases.pop() {
-        Some(release) => Ok(release),
-        None => Err(eyre!("Adoptium endpoint did not return any valid releases")),
-    }
-}
-
-/// Attempts to get all the versions
-pub async fn get_releases(client: &Client) -> Result<BTreeMap<u64, Release>> {
-    let available = get_available_releases(client)
-        .await
-        .context("Failed to list adoptium releases")?;
-    let mut output = BTreeMap::new();
-    // Get the generally available version of all the available releases
-    for version in available.available_releases {
-        let release = get_release(client, version, "ga").await.with_context(|| {
-            format!(
-                "Failed to get version {} from the adoptium archive",
-                version
-            )
-        })?;
-        output.insert(version, release);
-    }
-    // See if we already have the latest version
-    if output.contains_key(&available.most_recent_feature_version) {
-        // Go ahead and return
-        Ok(output)
-    } else {
+/// Eclipse Temurin, from the adoptium api
+pub struct Adoptium {
+    /// Base url of the api
+    api: String,
+    /// Releases the api lists, looked up once per run
+    available: OnceCell<AvailableReleases>,
+}
+
+impl Adoptium {
+    /// Sets up temurin with the configured api
+    pub fn new(config: &Config) -> Self {
+        Adoptium {
+            api: config.adoptium_api.clone(),
+            available: OnceCell::new(),
+        }
+    }
+
+    /// Attempts to get the available releases
+    pub async fn get_available_releases(&self, ctx: &Context) -> Result<&AvailableReleases> {
+        self.available
+            .get_or_try_init(|| async {
+                let endpoint = api_url(&self.api, "v3/info/available_releases?jvm_impl=hotspot");
+                let mut response = retry::send(&ctx.client, ctx.client.get(&endpoint).build())
+                    .await
+                    .context("Failed to request available versions from adoptium")?;
+                response
+                    .body_json()
+                    .await
+                    .map_err(|e| eyre!(e))
+                    .context("Failed to request available versions from adoptium")
+                    .with_section(|| endpoint.clone().header("Failed Request:"))
+            })
+            .await
+    }
+
+    /// Attempts to get every build of a particular version, newest first
+    ///
+    /// Walks all the pages of results, so this is also suitable for historical queries
+    pub async fn get_feature_releases(
+        &self,
+        ctx: &Context,
+        version: u64,
+        release_type: &str,
+        platform: Platform,
+    ) -> Result<Vec<Release>> {
+        let endpoint = api_url(
+            &self.api,
+            &format!("v3/assets/feature_releases/{}/{}", version, release_type),
+        );
+        let request = ctx
+            .client
+            .get(endpoint)
+            .query(&ReleaseQuery {
+                architecture: platform.arch.name().to_string(),
+                heap_size: "normal".to_string(),
+                image_type: "jdk".to_string(),
+                os: os_name(platform.os).to_string(),
+                page: 0,
+                page_size: PAGE_SIZE,
+                project: "jdk".to_string(),
+                jvm_impl: "hotspot".to_string(),
+                sort_method: "DEFAULT".to_string(),
+                sort_order: "DESC".to_string(),
+            })
+            .map_err(|e| eyre!(e))
+            .context("Failed to build request")?
+            .build();
+        let query = request.url().as_str().to_string();
+        pager::get_all(&ctx.client, request, NotFound::End)
+            .await
+            .context("Failed to get release information from adoptium")
+            .with_section(move || query.header("Failed Request"))
+    }
+
+    /// Attempts to get the release info for a particular version
+    ///
+    /// Returns `None` if the version is not published for the given platform
+    pub async fn get_release(
+        &self,
+        ctx: &Context,
+        version: u64,
+        release_type: &str,
+        platform: Platform,
+    ) -> Result<Option<Release>> {
+        let releases = self
+            .get_feature_releases(ctx, version, release_type, platform)
+            .await?;
+        Ok(releases.into_iter().max())
+    }
+
+    /// Attempts to get all the versions for a platform
+    ///
+    /// Versions that are not published for the platform are omitted
+    pub async fn get_releases(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, Release>> {
+        let available = self
+            .get_available_releases(ctx)
+            .await
+            .context("Failed to list temurin releases")?;
+        // Get the generally available version of all the available releases
+        let releases = try_join_all(available.available_releases.iter().map(|&version| {
+            self.get_release(ctx, version, "ga", platform)
+                .map(move |release| {
+                    release.with_context(|| {
+                        format!(
+                            "Failed to get version {} for {} from the adoptium archive",
+                            version, platform
+                        )
+                    })
+                })
+        }))
+        .await?;
+        let mut output: BTreeMap<u64, Release> = available
+            .available_releases
+            .iter()
+            .copied()
+            .zip(releases)
+            .filter_map(|(version, release)| Some((version, release?)))
+            .collect();
+        // See if we already have the latest version
         let version = available.most_recent_feature_version;
+        if output.contains_key(&version) {
+            return Ok(output);
+        }
         // Otherwise try to get an EA version of it
-        let release = get_release(client, version, "ea").await.with_context(|| {
-            format!(
-                "Failed to get version {} (latest) from the adoptium archive",
-                version
-            )
-        })?;
-        output.insert(version, release);
+        let release = self
+            .get_release(ctx, version, "ea", platform)
+            .await
+            .with_context(|| {
+                format!(
+                    "Failed to get version {} (latest) for {} from the adoptium archive",
+                    version, platform
+                )
+            })?;
+        output.extend(release.map(|release| (version, release)));
         Ok(output)
     }
 }
+
+#[async_trait]
+impl Vendor for Adoptium {
+    fn name(&self) -> &'static str {
+        "temurin"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let available = self.get_available_releases(ctx).await?;
+        Ok(Channels::resolve(
+            self.name(),
+            available.most_recent_feature_version,
+            Some(available.most_recent_feature_release),
+            available.available_lts_releases.iter().copied().max(),
+        ))
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        self.get_releases(ctx, platform)
+            .await?
+            .into_iter()
+            .map(|(version, release)| Ok((version, release.into_package()?)))
+            .collect()
+    }
+}
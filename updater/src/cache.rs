@@ -0,0 +1,37 @@
+use std::{collections::HashMap, path::Path};
+
+use color_eyre::eyre::{Context, Result};
+
+use crate::{Release, SourcesFile, System};
+
+/// Hashes from a previous run, keyed by package link
+#[derive(Debug, Clone, Default)]
+pub struct HashCache {
+    hashes: HashMap<String, String>,
+}
+
+impl HashCache {
+    /// Builds a cache out of a previously generated `sources.json`
+    pub fn load(path: &Path) -> Result<Self> {
+        let sources = SourcesFile::load(path).context("Failed to load previous sources")?;
+        Ok(Self::from_systems(
+            sources.systems().map(|(_, system)| system),
+        ))
+    }
+
+    /// Builds a cache out of the releases in some systems
+    pub fn from_systems<'a>(systems: impl IntoIterator<Item = &'a System>) -> Self {
+        let mut hashes = HashMap::new();
+        for (_, _, Release { link, sha256, .. }) in systems.into_iter().flat_map(System::entries) {
+            if !sha256.is_empty() {
+                hashes.insert(link.clone(), sha256.clone());
+            }
+        }
+        HashCache { hashes }
+    }
+
+    /// Looks up the hash previously computed for a link
+    pub fn get(&self, link: &str) -> Option<&str> {
+        self.hashes.get(link).map(String::as_str)
+    }
+}
@@ -0,0 +1,358 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write as _},
+};
+
+use clap::ValueEnum;
+
+use crate::{Release, Sources, SourcesFile};
+
+/// Channels every vendor's sources carry
+const CHANNELS: [&str; 3] = ["latest", "stable", "lts"];
+
+/// How a changelog is rendered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum Format {
+    /// Plain text, suitable for a commit body
+    #[default]
+    Text,
+    /// Markdown, suitable for a pull request description
+    Markdown,
+}
+
+/// A single difference in one vendor's sources for one system
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// A major version is newly available
+    Added { major: u64, java_version: String },
+    /// A major version is no longer available
+    Removed { major: u64, java_version: String },
+    /// A major version moved to a newer (or older) release
+    Updated {
+        major: u64,
+        from: String,
+        to: String,
+    },
+    /// A major version went from an early access build to a general availability release
+    Promoted {
+        major: u64,
+        from: String,
+        to: String,
+    },
+    /// The release is the same, but its link or hash changed
+    Repackaged { major: u64, java_version: String },
+    /// A channel now points at a different major version
+    ChannelMoved {
+        channel: &'static str,
+        from: Option<u64>,
+        to: Option<u64>,
+    },
+}
+
+impl Change {
+    /// Describes the change, wrapping versions with `code`
+    fn describe(&self, code: &str) -> String {
+        let v = |version: &str| format!("{}{}{}", code, version, code);
+        match self {
+            Change::Added {
+                major,
+                java_version,
+            } => format!("jdk{} added at {}", major, v(java_version)),
+            Change::Removed {
+                major,
+                java_version,
+            } => format!("jdk{} removed, was {}", major, v(java_version)),
+            Change::Updated { major, from, to } => {
+                format!("jdk{} updated {} -> {}", major, v(from), v(to))
+            }
+            Change::Promoted { major, from, to } => format!(
+                "jdk{} promoted from early access {} -> {}",
+                major,
+                v(from),
+                v(to)
+            ),
+            Change::Repackaged {
+                major,
+                java_version,
+            } => format!("jdk{} repackaged at {}", major, v(java_version)),
+            Change::ChannelMoved { channel, from, to } => match (from, to) {
+                (Some(from), Some(to)) => format!("{} moved jdk{} -> jdk{}", channel, from, to),
+                (None, Some(to)) => format!("{} set to jdk{}", channel, to),
+                (Some(from), None) => format!("{} unset, was jdk{}", channel, from),
+                (None, None) => format!("{} unchanged", channel),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.describe(""))
+    }
+}
+
+/// Every change between two sets of sources, grouped by system and vendor
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changelog {
+    groups: BTreeMap<(String, String), Vec<Change>>,
+}
+
+impl Changelog {
+    /// Compares the old sources against the new ones
+    pub fn new(old: &SourcesFile, new: &SourcesFile) -> Self {
+        let mut groups = BTreeMap::new();
+        let names: BTreeSet<&str> = old
+            .systems()
+            .chain(new.systems())
+            .map(|(name, _)| name)
+            .collect();
+        for name in names {
+            let old = old.system(name);
+            let new = new.system(name);
+            let vendors: BTreeSet<&str> = old
+                .into_iter()
+                .chain(new)
+                .flat_map(|system| system.vendors().map(|(vendor, _)| vendor))
+                .collect();
+            for vendor in vendors {
+                let changes = sources_changes(
+                    old.and_then(|system| system.vendor(vendor)),
+                    new.and_then(|system| system.vendor(vendor)),
+                );
+                if !changes.is_empty() {
+                    groups.insert((name.to_string(), vendor.to_string()), changes);
+                }
+            }
+        }
+        Changelog { groups }
+    }
+
+    /// Whether the sources are identical
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Iterates over the changes of each system and vendor
+    pub fn groups(&self) -> impl Iterator<Item = (&str, &str, &[Change])> {
+        self.groups.iter().map(|((system, vendor), changes)| {
+            (system.as_str(), vendor.as_str(), changes.as_slice())
+        })
+    }
+
+    /// One line summary, e.g. for a commit subject
+    pub fn summary(&self) -> String {
+        if self.is_empty() {
+            return "No changes".to_string();
+        }
+        let vendors: BTreeSet<&str> = self.groups().map(|(_, vendor, _)| vendor).collect();
+        let systems: BTreeSet<&str> = self.groups().map(|(system, _, _)| system).collect();
+        format!(
+            "Update {} for {} system{}",
+            vendors.into_iter().collect::<Vec<_>>().join(", "),
+            systems.len(),
+            if systems.len() == 1 { "" } else { "s" }
+        )
+    }
+
+    /// Renders the changelog in the given format
+    pub fn render(&self, format: Format) -> String {
+        let mut output = String::new();
+        for (i, (system, vendor, changes)) in self.groups().enumerate() {
+            if i > 0 {
+                output.push('\n');
+            }
+            match format {
+                Format::Text => {
+                    let _ = writeln!(output, "{} {}:", system, vendor);
+                    for change in changes {
+                        let _ = writeln!(output, "  {}", change.describe(""));
+                    }
+                }
+                Format::Markdown => {
+                    let _ = writeln!(output, "### {} `{}`\n", vendor, system);
+                    for change in changes {
+                        let _ = writeln!(output, "- {}", change.describe("`"));
+                    }
+                }
+            }
+        }
+        output
+    }
+}
+
+impl fmt::Display for Changelog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Format::Text))
+    }
+}
+
+/// Lists the changes between one vendor's old and new sources for a system
+fn sources_changes(old: Option<&Sources>, new: Option<&Sources>) -> Vec<Change> {
+    let (old_majors, new_majors) = (majors(old), majors(new));
+    let all: BTreeSet<u64> = old_majors
+        .keys()
+        .chain(new_majors.keys())
+        .copied()
+        .collect();
+    let mut changes: Vec<Change> = all
+        .into_iter()
+        .filter_map(
+            |major| match (old_majors.get(&major), new_majors.get(&major)) {
+                (None, Some(new)) => Some(Change::Added {
+                    major,
+                    java_version: new.java_version.clone(),
+                }),
+                (Some(old), None) => Some(Change::Removed {
+                    major,
+                    java_version: old.java_version.clone(),
+                }),
+                (Some(old), Some(new)) if old == new => None,
+                (Some(old), Some(new)) if old.early_access && !new.early_access => {
+                    Some(Change::Promoted {
+                        major,
+                        from: old.java_version.clone(),
+                        to: new.java_version.clone(),
+                    })
+                }
+                (Some(old), Some(new)) if old.java_version != new.java_version => {
+                    Some(Change::Updated {
+                        major,
+                        from: old.java_version.clone(),
+                        to: new.java_version.clone(),
+                    })
+                }
+                (Some(_), Some(new)) => Some(Change::Repackaged {
+                    major,
+                    java_version: new.java_version.clone(),
+                }),
+                (None, None) => None,
+            },
+        )
+        .collect();
+    for channel in CHANNELS {
+        let major = |sources: Option<&Sources>| Some(sources?.get(channel)?.major_version);
+        let (from, to) = (major(old), major(new));
+        if from != to {
+            changes.push(Change::ChannelMoved { channel, from, to });
+        }
+    }
+    changes
+}
+
+/// Releases of some sources keyed by major version
+fn majors(sources: Option<&Sources>) -> BTreeMap<u64, &Release> {
+    sources
+        .into_iter()
+        .flat_map(|sources| sources.versions.values())
+        .map(|release| (release.major_version, release))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+
+    /// A release as it appears in sources.json
+    fn release(major: u64, java_version: &str, early_access: bool, sha256: &str) -> Value {
+        json!({
+            "link": format!("https://example.com/jdk-{}.tar.gz", java_version),
+            "major_version": major,
+            "java_version": java_version,
+            "early_access": early_access,
+            "sha256": sha256,
+        })
+    }
+
+    /// Sources of one vendor for x86_64-linux, with every channel on `channel`
+    fn sources(releases: &[Value], channel: usize) -> SourcesFile {
+        let versions: serde_json::Map<String, Value> = releases
+            .iter()
+            .map(|release| (format!("jdk{}", release["major_version"]), release.clone()))
+            .collect();
+        let channel = &releases[channel];
+        serde_json::from_value(json!({
+            "x86_64-linux": {
+                "temurin": {
+                    "versions": versions,
+                    "latest": channel,
+                    "stable": channel,
+                    "lts": channel,
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    fn changes(changelog: &Changelog) -> Vec<String> {
+        changelog
+            .groups()
+            .flat_map(|(system, vendor, changes)| {
+                changes
+                    .iter()
+                    .map(move |change| format!("{} {}: {}", system, vendor, change))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn identical_sources_have_no_changes() {
+        let old = sources(&[release(17, "17.0.8+7", false, "a")], 0);
+        let changelog = Changelog::new(&old, &old.clone());
+        assert!(changelog.is_empty());
+        assert_eq!(changelog.summary(), "No changes");
+    }
+
+    #[test]
+    fn classifies_each_kind_of_change() {
+        let old = sources(
+            &[
+                release(11, "11.0.20+8", false, "a"),
+                release(17, "17.0.8+7", false, "b"),
+                release(21, "21-beta+35", true, "c"),
+                release(22, "22.0.1+8", false, "d"),
+            ],
+            1,
+        );
+        let new = sources(
+            &[
+                release(17, "17.0.9+9", false, "e"),
+                release(21, "21+35", false, "f"),
+                release(22, "22.0.1+8", false, "g"),
+                release(23, "23-ea+5", true, "h"),
+            ],
+            1,
+        );
+        let changelog = Changelog::new(&old, &new);
+        assert_eq!(
+            changes(&changelog),
+            [
+                "x86_64-linux temurin: jdk11 removed, was 11.0.20+8",
+                "x86_64-linux temurin: jdk17 updated 17.0.8+7 -> 17.0.9+9",
+                "x86_64-linux temurin: jdk21 promoted from early access 21-beta+35 -> 21+35",
+                "x86_64-linux temurin: jdk22 repackaged at 22.0.1+8",
+                "x86_64-linux temurin: jdk23 added at 23-ea+5",
+                "x86_64-linux temurin: latest moved jdk17 -> jdk21",
+                "x86_64-linux temurin: stable moved jdk17 -> jdk21",
+                "x86_64-linux temurin: lts moved jdk17 -> jdk21",
+            ]
+        );
+        assert_eq!(changelog.summary(), "Update temurin for 1 system");
+    }
+
+    #[test]
+    fn vendors_and_systems_that_disappear_are_removed() {
+        let old = sources(&[release(17, "17.0.8+7", false, "a")], 0);
+        let changelog = Changelog::new(&old, &SourcesFile::default());
+        assert_eq!(
+            changes(&changelog),
+            [
+                "x86_64-linux temurin: jdk17 removed, was 17.0.8+7",
+                "x86_64-linux temurin: latest unset, was jdk17",
+                "x86_64-linux temurin: stable unset, was jdk17",
+                "x86_64-linux temurin: lts unset, was jdk17",
+            ]
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::path::PathBuf;
+
+use clap::{ArgAction, Args, Parser, Subcommand};
+use color_eyre::{
+    eyre::{eyre, Result},
+    Help,
+};
+
+use updater::{
+    changelog::Format,
+    config::{
+        Config, HashMode, DEFAULT_ADOPTIUM_API, DEFAULT_AZUL_API, DEFAULT_CONCURRENCY,
+        DEFAULT_CORRETTO_API, DEFAULT_GITHUB_API, DEFAULT_LIBERICA_API, DEFAULT_MARKETPLACE_API,
+        DEFAULT_OPENJDK_EA_API, DEFAULT_SAPMACHINE_API,
+    },
+    platform::Platform,
+    vendor, Selection,
+};
+
+/// Keeps sources.json pinned to the latest JDK releases
+#[derive(Parser, Debug)]
+#[command(version, about)]
+pub struct Cli {
+    /// Path of the sources file to read and write
+    #[arg(short, long, global = true, default_value = "sources.json")]
+    pub sources: PathBuf,
+    /// Only process these vendors, defaults to all of them
+    #[arg(long = "vendor", global = true, value_delimiter = ',')]
+    pub vendors: Vec<String>,
+    /// Only process these nix systems, defaults to all of them
+    #[arg(long = "system", global = true, value_delimiter = ',')]
+    pub systems: Vec<String>,
+    /// Print more progress information, repeat to also print every request and reused hash
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+    /// Only print errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+    #[command(flatten)]
+    pub fetch: FetchArgs,
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Options controlling how releases are fetched
+#[derive(Args, Debug)]
+pub struct FetchArgs {
+    /// How package hashes are determined
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        env = "UPDATER_HASH_MODE",
+        default_value_t
+    )]
+    pub hash_mode: HashMode,
+    /// Maximum number of concurrent requests to a single host
+    #[arg(long, global = true, env = "UPDATER_CONCURRENCY", default_value_t = DEFAULT_CONCURRENCY)]
+    pub concurrency: usize,
+    /// Hash every package again, instead of reusing hashes from the sources file
+    ///
+    /// Only the checksum hash mode reuses hashes, prefetch and verify always download packages
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+    /// Base url of the adoptium api
+    #[arg(long, global = true, env = "UPDATER_ADOPTIUM_API", default_value = DEFAULT_ADOPTIUM_API)]
+    pub adoptium_api: String,
+    /// Base url of the azul metadata api
+    #[arg(long, global = true, env = "UPDATER_AZUL_API", default_value = DEFAULT_AZUL_API)]
+    pub azul_api: String,
+    /// Base url the corretto index is published under
+    #[arg(long, global = true, env = "UPDATER_CORRETTO_API", default_value = DEFAULT_CORRETTO_API)]
+    pub corretto_api: String,
+    /// Base url of the bellsoft api
+    #[arg(long, global = true, env = "UPDATER_LIBERICA_API", default_value = DEFAULT_LIBERICA_API)]
+    pub liberica_api: String,
+    /// Base url of the adoptium marketplace api
+    #[arg(long, global = true, env = "UPDATER_MARKETPLACE_API", default_value = DEFAULT_MARKETPLACE_API)]
+    pub marketplace_api: String,
+    /// Base url of jdk.java.net, where the openjdk early access builds are listed
+    #[arg(long, global = true, env = "UPDATER_OPENJDK_EA_API", default_value = DEFAULT_OPENJDK_EA_API)]
+    pub openjdk_ea_api: String,
+    /// Base url the sapmachine release list is published under
+    #[arg(long, global = true, env = "UPDATER_SAPMACHINE_API", default_value = DEFAULT_SAPMACHINE_API)]
+    pub sapmachine_api: String,
+    /// Base url of the github api
+    #[arg(long, global = true, env = "UPDATER_GITHUB_API", default_value = DEFAULT_GITHUB_API)]
+    pub github_api: String,
+    /// Token to authenticate to the github api with, to avoid its rate limit
+    #[arg(long, global = true, env = "GITHUB_TOKEN", hide_env_values = true)]
+    pub github_token: Option<String>,
+}
+
+/// Updater subcommands
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Fetch the latest releases and write them to the sources file
+    Update {
+        /// Print the updated sources instead of writing them
+        #[arg(long)]
+        stdout: bool,
+    },
+    /// Report whether the sources file is out of date, without writing it
+    ///
+    /// Exits with status 2 if anything would change, and with 1 if the update itself failed.
+    Check,
+    /// Show what an update would change in the sources file
+    Diff {
+        /// How the changes are rendered
+        #[arg(long, value_enum, default_value_t)]
+        format: Format,
+    },
+    /// Print the pinned release for a vendor from the sources file
+    Show {
+        /// Vendor to look up, e.g. temurin
+        vendor: String,
+        /// Channel (latest, stable or lts) or version (e.g. jdk17) to look up
+        #[arg(default_value = "stable")]
+        version: String,
+    },
+}
+
+impl Cli {
+    /// Builds the updater configuration out of the arguments
+    pub fn config(&self) -> Config {
+        let previous_sources =
+            Some(self.sources.clone()).filter(|path| !self.fetch.no_cache && path.exists());
+        Config {
+            hash_mode: self.fetch.hash_mode,
+            concurrency: self.fetch.concurrency,
+            previous_sources,
+            adoptium_api: self.fetch.adoptium_api.clone(),
+            azul_api: self.fetch.azul_api.clone(),
+            corretto_api: self.fetch.corretto_api.clone(),
+            liberica_api: self.fetch.liberica_api.clone(),
+            marketplace_api: self.fetch.marketplace_api.clone(),
+            openjdk_ea_api: self.fetch.openjdk_ea_api.clone(),
+            sapmachine_api: self.fetch.sapmachine_api.clone(),
+            github_api: self.fetch.github_api.clone(),
+            github_token: self.fetch.github_token.clone(),
+        }
+    }
+
+    /// The vendors and systems selected with `--vendor` and `--system`
+    pub fn selection(&self) -> Result<Selection> {
+        let vendors = if self.vendors.is_empty() {
+            vendor::NAMES.to_vec()
+        } else {
+            self.vendors
+                .iter()
+                .map(|name| {
+                    vendor::NAMES
+                        .iter()
+                        .copied()
+                        .find(|vendor| vendor == name)
+                        .ok_or_else(|| eyre!("Unknown vendor: {}", name))
+                        .with_suggestion(|| {
+                            format!("Known vendors are {}", vendor::NAMES.join(", "))
+                        })
+                })
+                .collect::<Result<_>>()?
+        };
+        let platforms = if self.systems.is_empty() {
+            Platform::ALL.to_vec()
+        } else {
+            self.systems
+                .iter()
+                .map(|system| {
+                    Platform::from_nix_system(system)
+                        .ok_or_else(|| eyre!("Unknown system: {}", system))
+                        .with_suggestion(|| {
+                            let systems: Vec<_> =
+                                Platform::ALL.iter().map(|p| p.nix_system()).collect();
+                            format!("Known systems are {}", systems.join(", "))
+                        })
+                })
+                .collect::<Result<_>>()?
+        };
+        Ok(Selection { vendors, platforms })
+    }
+}
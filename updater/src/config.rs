@@ -0,0 +1,90 @@
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+
+/// Number of concurrent requests per host, if not configured
+pub const DEFAULT_CONCURRENCY: usize = 4;
+/// Base url of the adoptium api, if not overridden
+pub const DEFAULT_ADOPTIUM_API: &str = "https://api.adoptium.net";
+/// Base url of the azul metadata api, if not overridden
+pub const DEFAULT_AZUL_API: &str = "https://api.azul.com";
+/// Base url the corretto index is published under, if not overridden
+pub const DEFAULT_CORRETTO_API: &str = "https://corretto.github.io";
+/// Base url of the bellsoft api, if not overridden
+pub const DEFAULT_LIBERICA_API: &str = "https://api.bell-sw.com";
+/// Base url of the adoptium marketplace api, if not overridden
+pub const DEFAULT_MARKETPLACE_API: &str = "https://marketplace-api.adoptium.net";
+/// Base url of jdk.java.net, if not overridden
+pub const DEFAULT_OPENJDK_EA_API: &str = "https://jdk.java.net";
+/// Base url the sapmachine release list is published under, if not overridden
+pub const DEFAULT_SAPMACHINE_API: &str = "https://sap.github.io";
+/// Base url of the github api, if not overridden
+pub const DEFAULT_GITHUB_API: &str = "https://api.github.com";
+
+/// How the sha256 of a package is determined
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum HashMode {
+    /// Use the checksum published by the vendor, only downloading packages without one
+    #[default]
+    Checksum,
+    /// Download every package and hash it locally
+    Prefetch,
+    /// Download every package and check it against the published checksum and checksum file
+    Verify,
+}
+
+/// Updater configuration
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub hash_mode: HashMode,
+    /// Maximum number of requests in flight to a single host
+    pub concurrency: usize,
+    /// Previously generated sources, whose hashes are reused for unchanged links
+    pub previous_sources: Option<PathBuf>,
+    /// Base url of the adoptium api, e.g. to go through a caching proxy
+    pub adoptium_api: String,
+    /// Base url of the azul metadata api
+    pub azul_api: String,
+    /// Base url the corretto index is published under
+    pub corretto_api: String,
+    /// Base url of the bellsoft api
+    pub liberica_api: String,
+    /// Base url of the adoptium marketplace api
+    pub marketplace_api: String,
+    /// Base url of jdk.java.net
+    pub openjdk_ea_api: String,
+    /// Base url the sapmachine release list is published under
+    pub sapmachine_api: String,
+    /// Base url of the github api
+    pub github_api: String,
+    /// Token to authenticate to the github api with
+    pub github_token: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            hash_mode: HashMode::default(),
+            concurrency: DEFAULT_CONCURRENCY,
+            previous_sources: None,
+            adoptium_api: DEFAULT_ADOPTIUM_API.to_string(),
+            azul_api: DEFAULT_AZUL_API.to_string(),
+            corretto_api: DEFAULT_CORRETTO_API.to_string(),
+            liberica_api: DEFAULT_LIBERICA_API.to_string(),
+            marketplace_api: DEFAULT_MARKETPLACE_API.to_string(),
+            openjdk_ea_api: DEFAULT_OPENJDK_EA_API.to_string(),
+            sapmachine_api: DEFAULT_SAPMACHINE_API.to_string(),
+            github_api: DEFAULT_GITHUB_API.to_string(),
+            github_token: None,
+        }
+    }
+}
+
+/// Joins a path onto a configured base url, which may or may not end in a slash
+pub fn api_url(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
+}
@@ -0,0 +1,115 @@
+use color_eyre::eyre::{Context as _, Result};
+use surf::Client;
+
+use crate::{
+    cache::HashCache,
+    config::{Config, HashMode},
+    debug,
+    limit::HostLimiter,
+    prefetch::{self, PublishedChecksum},
+    redirect::FollowRedirects,
+};
+
+/// State shared by everything taking part in an update
+pub struct Context {
+    pub client: Client,
+    pub config: Config,
+    /// Hashes reused from the previous sources
+    pub cache: HashCache,
+}
+
+impl Context {
+    /// Sets up the client and loads the previous sources, if configured
+    pub fn new(config: Config) -> Result<Self> {
+        let cache = match &config.previous_sources {
+            Some(path) => HashCache::load(path).context("Failed to load previous hashes")?,
+            None => HashCache::default(),
+        };
+        Ok(Context {
+            // Release downloads are served through redirects, the limiter comes after the
+            // redirects so it sees the host each hop actually goes to
+            client: Client::new()
+                .with(FollowRedirects)
+                .with(HostLimiter::new(config.concurrency)),
+            config,
+            cache,
+        })
+    }
+
+    /// Gets the nix sha256 for a package, reusing the previous hash if the link is unchanged
+    pub async fn sha256(&self, link: &str, published: &PublishedChecksum) -> Result<String> {
+        if let Some(sha256) = self.cached(link) {
+            debug!("Reusing the previous hash of {}", link);
+            return Ok(sha256.to_string());
+        }
+        let sha256 = prefetch::hash_package(&self.client, &self.config, link, published)
+            .await
+            .context("Failed to hash package")?;
+        Ok(sha256.to_nix_base32())
+    }
+
+    /// The hash of a link from the previous sources, if the hash mode trusts it
+    ///
+    /// Only [`HashMode::Checksum`] reuses hashes, the other modes exist to download and check
+    /// every package again.
+    fn cached(&self, link: &str) -> Option<&str> {
+        match self.config.hash_mode {
+            HashMode::Checksum => self.cache.get(link),
+            HashMode::Prefetch | HashMode::Verify => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::SourcesFile;
+
+    const LINK: &str = "https://example.com/jdk-17.0.8+7.tar.gz";
+
+    /// A context whose previous sources pinned [`LINK`]
+    fn context(hash_mode: HashMode) -> Context {
+        let release = json!({
+            "link": LINK,
+            "major_version": 17,
+            "java_version": "17.0.8+7",
+            "early_access": false,
+            "sha256": "1c8sxs5kivsgzgl0rzjpy82pxp107r5yaflsfis86g94mimnaav4",
+        });
+        let sources: SourcesFile = serde_json::from_value(json!({
+            "x86_64-linux": {
+                "temurin": {
+                    "versions": { "jdk17": release },
+                    "latest": release,
+                    "stable": release,
+                    "lts": release,
+                },
+            },
+        }))
+        .unwrap();
+        Context {
+            client: Client::new(),
+            config: Config {
+                hash_mode,
+                ..Config::default()
+            },
+            cache: HashCache::from_systems(sources.systems().map(|(_, system)| system)),
+        }
+    }
+
+    #[test]
+    fn checksum_mode_reuses_previous_hashes() {
+        assert_eq!(
+            context(HashMode::Checksum).cached(LINK),
+            Some("1c8sxs5kivsgzgl0rzjpy82pxp107r5yaflsfis86g94mimnaav4")
+        );
+    }
+
+    #[test]
+    fn prefetch_and_verify_hash_every_package_again() {
+        assert_eq!(context(HashMode::Prefetch).cached(LINK), None);
+        assert_eq!(context(HashMode::Verify).cached(LINK), None);
+    }
+}
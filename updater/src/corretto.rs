@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_lock::OnceCell;
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{api_url, Config},
+    context::Context,
+    platform::{Arch, Os, Platform},
+    prefetch::PublishedChecksum,
+    retry,
+    vendor::{self, Channels, Major, Vendor, CHANNEL_PLATFORM},
+};
+
+/// Where the resources listed in the index are downloaded from
+pub const DOWNLOAD_BASE: &str = "https://corretto.aws";
+
+/// Architectures amazon builds corretto for
+const ARCHES: &[Arch] = &[Arch::X86_64, Arch::Aarch64];
+
+/// A downloadable file from `latest_links/indexmap_with_checksum.json`
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Resource {
+    /// Path of the file, relative to [`DOWNLOAD_BASE`]
+    pub resource: String,
+    /// Hex encoded md5 of the file, which the index calls its checksum
+    #[serde(rename = "checksum")]
+    md5: String,
+    /// Hex encoded sha256 of the file
+    #[serde(rename = "checksum_sha256")]
+    pub sha256: Option<String>,
+}
+
+/// The index, keyed by os, architecture, package type, major version and archive type
+pub type Index =
+    HashMap<String, HashMap<String, HashMap<String, HashMap<String, HashMap<String, Resource>>>>>;
+
+impl Resource {
+    /// The corretto version of the file, e.g. `17.0.8.8.1`
+    pub fn corretto_version(&self) -> Option<&str> {
+        self.resource.split('/').rev().nth(1)
+    }
+
+    /// The openjdk version of the file as `java -version` reports it, e.g. `17.0.8+8` or
+    /// `1.8.0_382-b05`
+    ///
+    /// Corretto versions append the build and a corretto revision to the openjdk version, with
+    /// 8 using `8.<update>.<build>.<revision>` instead.
+    pub fn openjdk_version(&self) -> Option<String> {
+        let parts = self
+            .corretto_version()?
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        match parts.as_slice() {
+            [8, update, build, ..] => {
+                Some(vendor::format_jdk8_version(*update, false, Some(*build)))
+            }
+            [major, minor, security, build, ..] => Some(format!(
+                "{}+{}",
+                vendor::format_version(&[*major, *minor, *security]),
+                build
+            )),
+            _ => None,
+        }
+    }
+
+    /// Converts into a package of the vendor agnostic pipeline
+    pub fn to_package(&self, major: u64, os: Os) -> Result<vendor::Package> {
+        let java_version = self
+            .openjdk_version()
+            .ok_or_else(|| eyre!("Unrecognized corretto version"))
+            .with_section(|| self.resource.clone().header("Resource"))?;
+        Ok(vendor::Package {
+            link: api_url(DOWNLOAD_BASE, &self.resource),
+            major_version: major,
+            java_version,
+            early_access: false,
+            checksum: PublishedChecksum {
+                checksum: self.sha256.clone(),
+                checksum_link: None,
+            },
+            java_home: os.java_home().map(str::to_string),
+            distribution_version: None,
+        })
+    }
+}
+
+/// Amazon Corretto, from the published index of its latest releases
+pub struct Corretto {
+    /// Base url the index is published under
+    api: String,
+    /// The index, looked up once per run
+    index: OnceCell<Index>,
+}
+
+impl Corretto {
+    /// Sets up corretto with the configured api
+    pub fn new(config: &Config) -> Self {
+        Corretto {
+            api: config.corretto_api.clone(),
+            index: OnceCell::new(),
+        }
+    }
+
+    /// Attempts to get the index of the latest releases
+    pub async fn get_index(&self, ctx: &Context) -> Result<&Index> {
+        self.index
+            .get_or_try_init(|| async {
+                let endpoint = api_url(
+                    &self.api,
+                    "corretto-downloads/latest_links/indexmap_with_checksum.json",
+                );
+                let mut response = retry::send(&ctx.client, ctx.client.get(&endpoint).build())
+                    .await
+                    .context("Failed to request the corretto index")?;
+                response
+                    .body_json()
+                    .await
+                    .map_err(|e| eyre!(e))
+                    .context("Failed to decode the corretto index")
+                    .with_section(|| endpoint.clone().header("Failed Request:"))
+            })
+            .await
+    }
+
+    /// Attempts to get the latest tarball of every major for a platform
+    pub async fn get_resources(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, Resource>> {
+        Ok(resources(self.get_index(ctx).await?, platform))
+    }
+}
+
+/// Picks the latest tarball of every major for a platform out of the index
+pub fn resources(index: &Index, platform: Platform) -> BTreeMap<u64, Resource> {
+    if !ARCHES.contains(&platform.arch) {
+        return BTreeMap::new();
+    }
+    let majors = index
+        .get(platform.os.name())
+        .and_then(|archs| archs.get(platform.arch.name()))
+        .and_then(|types| types.get("jdk"));
+    majors
+        .into_iter()
+        .flatten()
+        .filter_map(|(major, archives)| {
+            Some((major.parse().ok()?, archives.get("tar.gz")?.clone()))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Vendor for Corretto {
+    fn name(&self) -> &'static str {
+        "corretto"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let majors = self
+            .get_resources(ctx, CHANNEL_PLATFORM)
+            .await
+            .context("Failed to list corretto releases")?;
+        // Corretto only publishes generally available releases
+        Channels::from_majors(
+            self.name(),
+            majors.keys().map(|&major| Major {
+                major,
+                early_access: false,
+                lts: vendor::is_lts(major),
+            }),
+        )
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        self.get_resources(ctx, platform)
+            .await?
+            .into_iter()
+            .map(|(major, resource)| Ok((major, resource.to_package(major, platform.os)?)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Tarballs of a platform in a trimmed copy of `latest_links/indexmap_with_checksum.json`
+    fn resources_of(arch: Arch, os: Os) -> BTreeMap<u64, Resource> {
+        let index: Index =
+            serde_json::from_str(include_str!("../fixtures/corretto_index.json")).unwrap();
+        resources(&index, Platform::new(arch, os))
+    }
+
+    #[test]
+    fn picks_the_jdk_tarballs_of_a_platform() {
+        let linux = resources_of(Arch::X86_64, Os::Linux);
+        assert_eq!(linux.keys().copied().collect::<Vec<_>>(), [8, 17]);
+        assert_eq!(
+            linux[&17].resource,
+            "/downloads/resources/17.0.8.8.1/amazon-corretto-17.0.8.8.1-linux-x64.tar.gz"
+        );
+        assert!(linux.values().all(|resource| resource.sha256.is_some()));
+
+        let darwin = resources_of(Arch::Aarch64, Os::Darwin);
+        assert_eq!(darwin.keys().copied().collect::<Vec<_>>(), [17]);
+        assert!(resources_of(Arch::Powerpc64le, Os::Linux).is_empty());
+    }
+
+    #[test]
+    fn converts_corretto_versions_to_openjdk_versions() {
+        let resources = resources_of(Arch::X86_64, Os::Linux);
+        assert_eq!(resources[&8].corretto_version(), Some("8.382.05.1"));
+        assert_eq!(
+            resources[&8].openjdk_version().as_deref(),
+            Some("1.8.0_382-b05")
+        );
+        assert_eq!(resources[&17].corretto_version(), Some("17.0.8.8.1"));
+        assert_eq!(
+            resources[&17].openjdk_version().as_deref(),
+            Some("17.0.8+8")
+        );
+    }
+
+    #[test]
+    fn packages_carry_the_published_sha256() {
+        let resource = &resources_of(Arch::X86_64, Os::Linux)[&17];
+        let package = resource.to_package(17, Os::Linux).unwrap();
+        assert_eq!(
+            package.link,
+            "https://corretto.aws/downloads/resources/17.0.8.8.1/amazon-corretto-17.0.8.8.1-linux-x64.tar.gz"
+        );
+        assert_eq!(package.java_version, "17.0.8+8");
+        assert_eq!(
+            package.checksum.checksum.as_deref(),
+            Some("23483160fce9d4aab73220413f509f03a3ab04a2e9c2fef51cd355ab09afab4e")
+        );
+        assert_eq!(package.checksum.checksum_link, None);
+    }
+}
@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+/// Ways working out which release each channel points at can fail
+#[derive(Debug, Error)]
+pub enum Error {
+    /// A vendor's metadata lists no release for a channel to track
+    #[error(
+        "{vendor} lists no release its {channel} channel could track (available: {})",
+        format_majors(available)
+    )]
+    NoRelease {
+        vendor: &'static str,
+        channel: &'static str,
+        /// Majors the vendor did list
+        available: Vec<u64>,
+    },
+}
+
+/// Lists majors like `jdk11, jdk17`
+fn format_majors(majors: &[u64]) -> String {
+    if majors.is_empty() {
+        return "none".to_string();
+    }
+    majors
+        .iter()
+        .map(|major| format!("jdk{}", major))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
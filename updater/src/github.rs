@@ -0,0 +1,122 @@
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use serde::{Deserialize, Serialize};
+use surf::Request;
+
+use crate::{
+    config::api_url,
+    context::Context,
+    pager::{self, NotFound},
+};
+
+/// Page size, the most the github api allows
+pub const PAGE_SIZE: u64 = 100;
+
+/// A file attached to a release
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+    /// Digest of the file, e.g. `sha256:<hex>`, for assets uploaded since github started
+    /// recording them
+    #[serde(default)]
+    pub digest: Option<String>,
+}
+
+impl Asset {
+    /// Hex encoded sha256 of the file, if github recorded one
+    pub fn sha256(&self) -> Option<String> {
+        self.digest
+            .as_deref()
+            .and_then(|digest| digest.strip_prefix("sha256:"))
+            .map(str::to_string)
+    }
+}
+
+/// A release from the `/repos/{owner}/{repo}/releases` endpoint
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub assets: Vec<Asset>,
+}
+
+impl Release {
+    /// Looks up an asset by its file name
+    pub fn asset(&self, name: &str) -> Option<&Asset> {
+        self.assets.iter().find(|asset| asset.name == name)
+    }
+}
+
+/// A repository from the `/orgs/{org}/repos` endpoint
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct Repository {
+    pub name: String,
+    #[serde(default)]
+    pub archived: bool,
+}
+
+/// Page query struct
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PageQuery {
+    pub per_page: u64,
+}
+
+/// Builds a request for the first page of a listing endpoint
+///
+/// Requests are authenticated with the configured github token, if there is one, which raises
+/// the rate limit well above what a full update needs.
+fn list_request(ctx: &Context, path: &str) -> Result<Request> {
+    let endpoint = api_url(&ctx.config.github_api, path);
+    let mut request = ctx
+        .client
+        .get(endpoint)
+        .query(&PageQuery {
+            per_page: PAGE_SIZE,
+        })
+        .map_err(|e| eyre!(e))
+        .context("Failed to build request")?
+        .header("Accept", "application/vnd.github+json")
+        // The github api rejects requests without a user agent
+        .header("User-Agent", concat!("updater/", env!("CARGO_PKG_VERSION")))
+        .build();
+    if let Some(token) = &ctx.config.github_token {
+        request.insert_header("Authorization", format!("Bearer {}", token));
+    }
+    Ok(request)
+}
+
+/// Attempts to get every published release of a repository, newest first
+pub async fn get_releases(ctx: &Context, repo: &str) -> Result<Vec<Release>> {
+    let request = list_request(ctx, &format!("repos/{}/releases", repo))?;
+    let query = request.url().as_str().to_string();
+    let releases: Vec<Release> = pager::get_all(&ctx.client, request, NotFound::Error)
+        .await
+        .with_context(|| format!("Failed to get releases of {} from github", repo))
+        .with_section(move || query.header("Failed Request"))
+        .suggestion("Set GITHUB_TOKEN if the github rate limit was hit")?;
+    Ok(releases
+        .into_iter()
+        .filter(|release| !release.draft)
+        .collect())
+}
+
+/// Attempts to get every repository of an organization that hasn't been archived
+pub async fn get_repositories(ctx: &Context, org: &str) -> Result<Vec<Repository>> {
+    let request = list_request(ctx, &format!("orgs/{}/repos", org))?;
+    let query = request.url().as_str().to_string();
+    let repositories: Vec<Repository> = pager::get_all(&ctx.client, request, NotFound::Error)
+        .await
+        .with_context(|| format!("Failed to get repositories of {} from github", org))
+        .with_section(move || query.header("Failed Request"))
+        .suggestion("Set GITHUB_TOKEN if the github rate limit was hit")?;
+    Ok(repositories
+        .into_iter()
+        .filter(|repository| !repository.archived)
+        .collect())
+}
@@ -0,0 +1,310 @@
+use std::collections::BTreeMap;
+
+use async_lock::OnceCell;
+use async_trait::async_trait;
+use color_eyre::eyre::{Context as _, Result};
+
+use crate::{
+    context::Context,
+    github,
+    platform::{Arch, Platform},
+    prefetch::PublishedChecksum,
+    vendor::{self, Channels, Major, Vendor},
+};
+
+/// Repository the community edition is released from
+pub const REPO: &str = "graalvm/graalvm-ce-builds";
+
+/// Architectures graalvm is built for
+const ARCHES: &[Arch] = &[Arch::X86_64, Arch::Aarch64];
+
+/// A release of GraalVM for a particular JDK, from a `jdk-<version>` tag
+///
+/// Releases from before GraalVM was versioned by its JDK (`vm-<version>` tags) bundle several
+/// majors without saying which JDK version they're built on, so they are left out
+#[derive(Debug, Clone)]
+pub struct Release {
+    /// Version of the JDK, e.g. `[21, 0, 2]`
+    pub version: Vec<u64>,
+    pub release: github::Release,
+}
+
+impl Release {
+    /// Picks out the releases for a JDK out of some github releases
+    pub fn from_github(release: github::Release) -> Option<Self> {
+        if release.prerelease {
+            return None;
+        }
+        let version = release
+            .tag_name
+            .strip_prefix("jdk-")?
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<Vec<u64>>>()?;
+        Some(Release { version, release })
+    }
+
+    /// Major version of the JDK
+    pub fn major(&self) -> u64 {
+        self.version[0]
+    }
+
+    /// Version of the JDK, e.g. `21.0.2`
+    pub fn java_version(&self) -> String {
+        self.release.tag_name["jdk-".len()..].to_string()
+    }
+
+    /// Version of GraalVM itself, from the release name, e.g. `21.0.2` out of
+    /// `GraalVM Community 21.0.2`
+    pub fn graalvm_version(&self) -> String {
+        self.release
+            .name
+            .as_deref()
+            .and_then(|name| name.rsplit(' ').next())
+            .filter(|version| !version.is_empty())
+            .map_or_else(|| self.java_version(), str::to_string)
+    }
+
+    /// File name of the archive for a platform
+    pub fn archive_name(&self, platform: Platform) -> Option<String> {
+        ARCHES.contains(&platform.arch).then(|| {
+            format!(
+                "graalvm-community-jdk-{}_{}-{}_bin.tar.gz",
+                self.java_version(),
+                platform.os.name(),
+                platform.arch.name()
+            )
+        })
+    }
+
+    /// Converts the archive for a platform into a package of the vendor agnostic pipeline
+    pub fn to_package(&self, platform: Platform) -> Option<vendor::Package> {
+        let name = self.archive_name(platform)?;
+        let asset = self.release.asset(&name)?;
+        Some(vendor::Package {
+            link: asset.browser_download_url.clone(),
+            major_version: self.major(),
+            java_version: self.java_version(),
+            early_access: false,
+            checksum: PublishedChecksum {
+                checksum: asset.sha256(),
+                checksum_link: self
+                    .release
+                    .asset(&format!("{}.sha256", name))
+                    .map(|asset| asset.browser_download_url.clone()),
+            },
+            java_home: platform.os.java_home().map(str::to_string),
+            distribution_version: Some(self.graalvm_version()),
+        })
+    }
+}
+
+/// Picks the newest release of each major that has an archive for a platform
+pub fn packages(releases: &[Release], platform: Platform) -> BTreeMap<u64, vendor::Package> {
+    let mut newest: BTreeMap<u64, &Release> = BTreeMap::new();
+    for release in releases {
+        if release.to_package(platform).is_none() {
+            continue;
+        }
+        match newest.get(&release.major()) {
+            Some(current) if current.version >= release.version => {}
+            _ => {
+                newest.insert(release.major(), release);
+            }
+        }
+    }
+    newest
+        .into_iter()
+        .filter_map(|(major, release)| Some((major, release.to_package(platform)?)))
+        .collect()
+}
+
+/// GraalVM Community Edition, from its github releases
+pub struct GraalVm {
+    /// Releases of every JDK, looked up once per run
+    releases: OnceCell<Vec<Release>>,
+}
+
+impl Default for GraalVm {
+    fn default() -> Self {
+        GraalVm {
+            releases: OnceCell::new(),
+        }
+    }
+}
+
+impl GraalVm {
+    /// Attempts to get every release for a JDK
+    pub async fn get_releases(&self, ctx: &Context) -> Result<&[Release]> {
+        self.releases
+            .get_or_try_init(|| async {
+                let releases = github::get_releases(ctx, REPO).await?;
+                Ok::<_, color_eyre::eyre::Report>(
+                    releases
+                        .into_iter()
+                        .filter_map(Release::from_github)
+                        .collect(),
+                )
+            })
+            .await
+            .map(Vec::as_slice)
+    }
+}
+
+#[async_trait]
+impl Vendor for GraalVm {
+    fn name(&self) -> &'static str {
+        "graalvm"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let releases = self
+            .get_releases(ctx)
+            .await
+            .context("Failed to list graalvm releases")?;
+        Channels::from_majors(
+            self.name(),
+            releases.iter().map(|release| Major {
+                major: release.major(),
+                early_access: false,
+                lts: vendor::is_lts(release.major()),
+            }),
+        )
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        Ok(packages(self.get_releases(ctx).await?, platform))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::Os;
+
+    use super::*;
+
+    /// A github release of graalvm with archives for the given platforms, e.g. `linux-x64`
+    fn github_release(tag_name: &str, name: &str, platforms: &[&str]) -> github::Release {
+        let version = tag_name.strip_prefix("jdk-").unwrap_or(tag_name);
+        github::Release {
+            tag_name: tag_name.to_string(),
+            name: Some(name.to_string()),
+            draft: false,
+            prerelease: false,
+            assets: platforms
+                .iter()
+                .flat_map(|platform| {
+                    let archive =
+                        format!("graalvm-community-jdk-{}_{}_bin.tar.gz", version, platform);
+                    [format!("{}.sha256", archive), archive]
+                })
+                .map(|name| github::Asset {
+                    browser_download_url: format!("https://github.com/{}", name),
+                    digest: None,
+                    name,
+                })
+                .collect(),
+        }
+    }
+
+    fn releases() -> Vec<Release> {
+        [
+            github_release(
+                "jdk-21.0.2",
+                "GraalVM Community 21.0.2",
+                &["linux-x64", "linux-aarch64", "macos-aarch64"],
+            ),
+            github_release(
+                "jdk-21.0.1",
+                "GraalVM Community 21.0.1",
+                &["linux-x64", "linux-aarch64", "macos-x64", "macos-aarch64"],
+            ),
+            github_release(
+                "jdk-17.0.9",
+                "GraalVM Community 17.0.9",
+                &["linux-x64", "macos-x64"],
+            ),
+            github_release("vm-22.3.1", "GraalVM Community 22.3.1", &["linux-amd64"]),
+        ]
+        .into_iter()
+        .filter_map(Release::from_github)
+        .collect()
+    }
+
+    #[test]
+    fn parses_jdk_tags() {
+        let release = Release::from_github(github_release(
+            "jdk-21.0.2",
+            "GraalVM Community 21.0.2",
+            &[],
+        ))
+        .unwrap();
+        assert_eq!(release.version, [21, 0, 2]);
+        assert_eq!(release.major(), 21);
+        assert_eq!(release.java_version(), "21.0.2");
+        assert_eq!(release.graalvm_version(), "21.0.2");
+    }
+
+    #[test]
+    fn skips_vm_tags_and_prereleases() {
+        assert!(
+            Release::from_github(github_release("vm-22.3.1", "GraalVM CE 22.3.1", &[])).is_none()
+        );
+        let mut prerelease = github_release("jdk-23.0.0", "GraalVM Community 23.0.0", &[]);
+        prerelease.prerelease = true;
+        assert!(Release::from_github(prerelease).is_none());
+    }
+
+    #[test]
+    fn graalvm_version_falls_back_to_the_java_version() {
+        let mut release = github_release("jdk-21.0.2", "", &[]);
+        assert_eq!(
+            Release::from_github(release.clone())
+                .unwrap()
+                .graalvm_version(),
+            "21.0.2"
+        );
+        release.name = None;
+        assert_eq!(
+            Release::from_github(release).unwrap().graalvm_version(),
+            "21.0.2"
+        );
+    }
+
+    #[test]
+    fn names_archives_by_platform() {
+        let release = &releases()[0];
+        assert_eq!(
+            release
+                .archive_name(Platform::new(Arch::Aarch64, Os::Darwin))
+                .as_deref(),
+            Some("graalvm-community-jdk-21.0.2_macos-aarch64_bin.tar.gz")
+        );
+        assert_eq!(
+            release.archive_name(Platform::new(Arch::S390x, Os::Linux)),
+            None
+        );
+    }
+
+    #[test]
+    fn picks_the_newest_release_with_an_archive_for_each_major() {
+        let releases = releases();
+        let linux = packages(&releases, Platform::new(Arch::X86_64, Os::Linux));
+        assert_eq!(linux.keys().copied().collect::<Vec<_>>(), [17, 21]);
+        assert_eq!(linux[&21].java_version, "21.0.2");
+        assert_eq!(linux[&21].distribution_version.as_deref(), Some("21.0.2"));
+        assert_eq!(
+            linux[&21].checksum.checksum_link.as_deref(),
+            Some("https://github.com/graalvm-community-jdk-21.0.2_linux-x64_bin.tar.gz.sha256")
+        );
+        // 21.0.2 has no intel mac archive, so 21.0.1 is the newest one there
+        let darwin = packages(&releases, Platform::new(Arch::X86_64, Os::Darwin));
+        assert_eq!(darwin[&21].java_version, "21.0.1");
+        assert_eq!(darwin[&17].java_version, "17.0.9");
+        assert!(packages(&releases, Platform::new(Arch::Powerpc64le, Os::Linux)).is_empty());
+    }
+}
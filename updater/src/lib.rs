@@ -0,0 +1,390 @@
+//! Data model and vendor clients behind sources.json
+//!
+//! Used by the updater binary, and by anything else that needs to read or query the sources file.
+
+use std::{collections::BTreeMap, fs, path::Path};
+
+use color_eyre::{
+    eyre::{Context as _, Result},
+    Help, SectionExt,
+};
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+
+/// Adoptium API
+pub mod adoptium;
+/// Hashes reused from previous runs
+pub mod cache;
+/// Differences between two sets of sources
+pub mod changelog;
+/// Updater configuration
+pub mod config;
+/// Shared update state
+pub mod context;
+/// Amazon Corretto
+pub mod corretto;
+/// Typed errors for channel resolution
+pub mod error;
+/// Github releases api
+pub mod github;
+/// GraalVM Community Edition
+pub mod graalvm;
+/// BellSoft Liberica
+pub mod liberica;
+/// Per host request limits
+pub mod limit;
+/// Progress reporting on stderr
+pub mod log;
+/// Microsoft Build of OpenJDK
+pub mod microsoft;
+/// OpenJDK early access builds
+pub mod openjdk_ea;
+/// Writing of the sources file
+pub mod output;
+/// Paginated api requests
+pub mod pager;
+/// Nix systems and their vendor names
+pub mod platform;
+/// Downloading and hashing of packages
+pub mod prefetch;
+/// Redirect following
+pub mod redirect;
+/// Retrying of failed requests
+pub mod retry;
+/// SAP SapMachine
+pub mod sapmachine;
+/// IBM Semeru
+pub mod semeru;
+/// JDK distributions
+pub mod vendor;
+/// Azul Zulu
+pub mod zulu;
+
+use context::Context;
+use error::Error;
+use platform::Platform;
+use vendor::{Channels, Package};
+
+/// Java release struct
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Release {
+    link: String,
+    major_version: u64,
+    java_version: String,
+    early_access: bool,
+    sha256: String,
+    /// Path of the JDK root inside the archive, when it isn't the top level directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    java_home: Option<String>,
+    /// Version of the distribution, when it is versioned separately from the JDK
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    distribution_version: Option<String>,
+}
+
+/// Sources serialization struct
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Sources {
+    versions: BTreeMap<String, Release>,
+    latest: Release,
+    stable: Release,
+    lts: Release,
+}
+
+/// System serialization struct, keyed by vendor name
+///
+/// Vendors that publish nothing for a system are omitted
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct System {
+    vendors: BTreeMap<String, Sources>,
+}
+
+/// A whole sources file, keyed by nix system
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct SourcesFile {
+    systems: BTreeMap<String, System>,
+}
+
+impl Release {
+    /// Where the archive is downloaded from
+    pub fn link(&self) -> &str {
+        &self.link
+    }
+
+    /// Major version of the JDK, e.g. `17`
+    pub fn major_version(&self) -> u64 {
+        self.major_version
+    }
+
+    /// Version as reported by `java -version`, e.g. `17.0.8+7`
+    pub fn java_version(&self) -> &str {
+        &self.java_version
+    }
+
+    /// Whether the release is an early access build rather than generally available
+    pub fn early_access(&self) -> bool {
+        self.early_access
+    }
+
+    /// Nix base32 sha256 of the archive
+    pub fn sha256(&self) -> &str {
+        &self.sha256
+    }
+
+    /// Path of the JDK root inside the archive, when it isn't the top level directory
+    pub fn java_home(&self) -> Option<&str> {
+        self.java_home.as_deref()
+    }
+
+    /// Version of the distribution, when it is versioned separately from the JDK
+    pub fn distribution_version(&self) -> Option<&str> {
+        self.distribution_version.as_deref()
+    }
+}
+
+impl System {
+    /// The sources for a vendor, if it has any for this system
+    pub fn vendor(&self, name: &str) -> Option<&Sources> {
+        self.vendors.get(name)
+    }
+
+    /// Replaces the sources of a vendor, removing them if `sources` is `None`
+    fn set_vendor(&mut self, name: &str, sources: Option<Sources>) {
+        match sources {
+            Some(sources) => {
+                self.vendors.insert(name.to_string(), sources);
+            }
+            None => {
+                self.vendors.remove(name);
+            }
+        }
+    }
+
+    /// Iterates over the vendors that have sources for this system
+    pub fn vendors(&self) -> impl Iterator<Item = (&str, &Sources)> {
+        self.vendors
+            .iter()
+            .map(|(name, sources)| (name.as_str(), sources))
+    }
+
+    /// Whether no vendor has sources for this system
+    pub fn is_empty(&self) -> bool {
+        self.vendors.is_empty()
+    }
+
+    /// Iterates over every version and channel of every vendor
+    pub fn entries(&self) -> impl Iterator<Item = (&str, String, &Release)> {
+        self.vendors().flat_map(|(vendor, sources)| {
+            sources
+                .entries()
+                .map(move |(version, release)| (vendor, version, release))
+        })
+    }
+}
+
+impl SourcesFile {
+    /// Reads a sources file
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .context("Failed to read sources")
+            .with_section(|| path.display().to_string().header("Path"))?;
+        serde_json::from_str(&contents)
+            .context("Failed to decode sources")
+            .with_section(|| path.display().to_string().header("Path"))
+    }
+
+    /// Validates and atomically writes the sources file
+    pub fn save(&self, path: &Path) -> Result<()> {
+        output::write_sources(path, self)
+    }
+
+    /// The sources of a system, e.g. `x86_64-linux`, if it has any
+    pub fn system(&self, name: &str) -> Option<&System> {
+        self.systems.get(name)
+    }
+
+    /// Iterates over the systems, keyed by nix system
+    pub fn systems(&self) -> impl Iterator<Item = (&str, &System)> {
+        self.systems
+            .iter()
+            .map(|(name, system)| (name.as_str(), system))
+    }
+
+    /// Whether no system has any sources
+    pub fn is_empty(&self) -> bool {
+        self.systems.is_empty()
+    }
+}
+
+impl Sources {
+    /// The release `latest` points at
+    pub fn latest(&self) -> &Release {
+        &self.latest
+    }
+
+    /// The release `stable` points at
+    pub fn stable(&self) -> &Release {
+        &self.stable
+    }
+
+    /// The release `lts` points at
+    pub fn lts(&self) -> &Release {
+        &self.lts
+    }
+
+    /// Iterates over the versions, e.g. `jdk17`, without the channels
+    pub fn versions(&self) -> impl Iterator<Item = (&str, &Release)> {
+        self.versions
+            .iter()
+            .map(|(version, release)| (version.as_str(), release))
+    }
+
+    /// Looks up a channel (`latest`, `stable` or `lts`) or a version such as `jdk17`
+    pub fn get(&self, version: &str) -> Option<&Release> {
+        match version {
+            "latest" => Some(&self.latest),
+            "stable" => Some(&self.stable),
+            "lts" => Some(&self.lts),
+            version => self.versions.get(version),
+        }
+    }
+
+    /// Iterates over the versions followed by the channels
+    pub fn entries(&self) -> impl Iterator<Item = (String, &Release)> {
+        self.versions
+            .iter()
+            .map(|(version, release)| (version.clone(), release))
+            .chain([
+                ("latest".to_string(), &self.latest),
+                ("stable".to_string(), &self.stable),
+                ("lts".to_string(), &self.lts),
+            ])
+    }
+
+    /// Builds the sources for a vendor out of its releases and the majors each channel tracks
+    ///
+    /// Returns `None` if a channel's major is missing from the releases, which after
+    /// [`Channels::fallback`] only happens when there are none
+    fn new(releases: BTreeMap<u64, Release>, channels: Channels) -> Option<Self> {
+        let [latest, stable, lts] = channels
+            .named()
+            .map(|(_, major)| releases.get(&major).cloned());
+        let (latest, stable, lts) = (latest?, stable?, lts?);
+        Some(Sources {
+            versions: releases
+                .into_iter()
+                .map(|(k, v)| (format!("jdk{}", k), v))
+                .collect(),
+            latest,
+            stable,
+            lts,
+        })
+    }
+}
+
+impl Release {
+    /// Converts a vendor's package, hashing it unless it was hashed in a previous run
+    pub async fn from_package(ctx: &Context, package: Package) -> Result<Self> {
+        let sha256 = ctx.sha256(&package.link, &package.checksum).await?;
+        Ok(Release {
+            link: package.link,
+            major_version: package.major_version,
+            java_version: package.java_version,
+            early_access: package.early_access,
+            sha256,
+            java_home: package.java_home,
+            distribution_version: package.distribution_version,
+        })
+    }
+}
+
+/// The vendors and systems a run covers
+#[derive(Debug, Clone)]
+pub struct Selection {
+    pub vendors: Vec<&'static str>,
+    pub platforms: Vec<Platform>,
+}
+
+impl Selection {
+    /// Whether a vendor is part of the selection
+    pub fn has_vendor(&self, vendor: &str) -> bool {
+        self.vendors.contains(&vendor)
+    }
+}
+
+/// Fetches the selected vendors and systems, returning `current` updated with what was fetched
+///
+/// Vendors that were fetched but publish nothing for a system are removed from it, vendors that
+/// were skipped keep their current sources.
+pub async fn update(
+    ctx: &Context,
+    current: &SourcesFile,
+    selection: &Selection,
+) -> Result<SourcesFile> {
+    let (vendors, fetched) = fetch_systems(ctx, selection).await?;
+    let mut updated = current.clone();
+    for (platform, fetched) in fetched {
+        let system = updated.systems.entry(platform.nix_system()).or_default();
+        for &vendor in &vendors {
+            system.set_vendor(vendor, fetched.vendor(vendor).cloned());
+        }
+    }
+    updated.systems.retain(|_, system| !system.is_empty());
+    Ok(updated)
+}
+
+/// Fetches the sources of the selected vendors for each of the selected systems
+///
+/// Vendors that list no release at all are skipped with a warning, so the rest of the run can
+/// go ahead. Returns the names of the vendors that were fetched along with the systems.
+pub async fn fetch_systems(
+    ctx: &Context,
+    selection: &Selection,
+) -> Result<(Vec<&'static str>, Vec<(Platform, System)>)> {
+    let vendors = vendor::by_names(&selection.vendors, &ctx.config);
+    // Find out what each vendor's channels track before going through the systems
+    let channels = try_join_all(vendors.iter().map(|vendor| async move {
+        match vendor.channels(ctx).await {
+            Ok(channels) => Ok(Some(channels)),
+            Err(e) if e.downcast_ref::<Error>().is_some() => {
+                let e = e.suggestion(format!(
+                    "Check the configured {} api, or leave the vendor out with --vendor",
+                    vendor.name()
+                ));
+                warning!("Skipping {}: {:?}", vendor.name(), e);
+                Ok(None)
+            }
+            Err(e) => Err(e).with_context(|| format!("Failed to get {} channels", vendor.name())),
+        }
+    }))
+    .await?;
+    let (vendors, channels): (Vec<_>, Vec<_>) = vendors
+        .into_iter()
+        .zip(channels)
+        .filter_map(|(vendor, channels)| Some((vendor, channels?)))
+        .unzip();
+    let names = vendors.iter().map(|vendor| vendor.name()).collect();
+    // Query all the systems at once, the client limits how many requests each host sees
+    let systems = try_join_all(selection.platforms.iter().map(|&platform| {
+        let (vendors, channels) = (&vendors, &channels);
+        async move {
+            info!("Fetching releases for {}", platform);
+            let sources = try_join_all(vendors.iter().zip(channels).map(
+                |(vendor, &channels)| async move {
+                    let sources =
+                        vendor::fetch_sources(ctx, vendor.as_ref(), channels, platform).await?;
+                    Ok::<_, color_eyre::eyre::Report>((vendor.name(), sources))
+                },
+            ))
+            .await?;
+            let mut system = System::default();
+            for (vendor, sources) in sources {
+                system.set_vendor(vendor, sources);
+            }
+            Ok::<_, color_eyre::eyre::Report>((platform, system))
+        }
+    }))
+    .await?;
+    Ok((names, systems))
+}
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{api_url, Config},
+    context::Context,
+    pager::{self, NotFound},
+    platform::{Arch, Os, Platform},
+    prefetch::PublishedChecksum,
+    vendor::{self, Channels, Major, Vendor, CHANNEL_PLATFORM},
+};
+
+/// Name of a 64 bit architecture in the bellsoft api, if bellsoft builds liberica for it
+fn arch_name(arch: Arch) -> Option<&'static str> {
+    match arch {
+        Arch::X86_64 => Some("x86"),
+        Arch::Aarch64 => Some("arm"),
+        Arch::Powerpc64le => Some("ppc"),
+        Arch::S390x => None,
+    }
+}
+
+/// Which bundle of liberica to get
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bundle {
+    /// The plain JDK
+    Jdk,
+    /// The JDK with JavaFX
+    JdkFull,
+}
+
+impl Bundle {
+    /// Name of the bundle in the bellsoft api
+    pub fn api_name(self) -> &'static str {
+        match self {
+            Bundle::Jdk => "jdk",
+            Bundle::JdkFull => "jdk-full",
+        }
+    }
+}
+
+/// Release from the `/v1/liberica/releases` endpoint
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Release {
+    pub feature_version: u64,
+    pub interim_version: u64,
+    pub update_version: u64,
+    pub patch_version: u64,
+    pub build_version: u64,
+    /// Version string, e.g. `17.0.8+7`
+    pub version: String,
+    pub download_url: String,
+    /// Hex encoded sha1 of the package, bellsoft doesn't publish a sha256
+    sha1: String,
+    #[serde(rename = "GA")]
+    pub ga: bool,
+    #[serde(rename = "LTS")]
+    pub lts: bool,
+}
+
+impl Release {
+    /// Sort key, newest last
+    fn version_key(&self) -> [u64; 5] {
+        [
+            self.feature_version,
+            self.interim_version,
+            self.update_version,
+            self.patch_version,
+            self.build_version,
+        ]
+    }
+
+    /// Converts into a package of the vendor agnostic pipeline
+    pub fn into_package(self, os: Os) -> vendor::Package {
+        vendor::Package {
+            link: self.download_url,
+            major_version: self.feature_version,
+            java_version: self.version,
+            early_access: !self.ga,
+            // Only a sha1 is published, so the package has to be downloaded to get its sha256
+            checksum: PublishedChecksum::default(),
+            java_home: os.java_home().map(str::to_string),
+            distribution_version: None,
+        }
+    }
+}
+
+/// Release query struct
+#[derive(Deserialize, Serialize, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct ReleaseQuery {
+    pub os: String,
+    pub arch: String,
+    pub bitness: u64,
+    pub package_type: String,
+    pub bundle_type: String,
+    pub version_modifier: String,
+    pub output: String,
+}
+
+/// BellSoft Liberica, from the bellsoft api
+pub struct Liberica {
+    /// Base url of the api
+    api: String,
+    bundle: Bundle,
+}
+
+impl Liberica {
+    /// Sets up a liberica bundle with the configured api
+    pub fn new(config: &Config, bundle: Bundle) -> Self {
+        Liberica {
+            api: config.liberica_api.clone(),
+            bundle,
+        }
+    }
+
+    /// Attempts to get the newest release of every major for a platform
+    ///
+    /// Returns nothing if bellsoft does not build liberica for the platform
+    pub async fn get_releases(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, Release>> {
+        let arch = match arch_name(platform.arch) {
+            Some(arch) => arch,
+            None => return Ok(BTreeMap::new()),
+        };
+        let endpoint = api_url(&self.api, "v1/liberica/releases");
+        let request = ctx
+            .client
+            .get(endpoint)
+            .query(&ReleaseQuery {
+                os: platform.os.name().to_string(),
+                arch: arch.to_string(),
+                bitness: 64,
+                package_type: "tar.gz".to_string(),
+                bundle_type: self.bundle.api_name().to_string(),
+                version_modifier: "latest".to_string(),
+                output: "json".to_string(),
+            })
+            .map_err(|e| eyre!(e))
+            .context("Failed to build request")?
+            .build();
+        let query = request.url().as_str().to_string();
+        let releases: Vec<Release> = pager::get_all(&ctx.client, request, NotFound::Error)
+            .await
+            .context("Failed to get release information from bellsoft")
+            .with_section(move || query.header("Failed Request"))?;
+        // Keep the newest release of each major
+        let mut output: BTreeMap<u64, Release> = BTreeMap::new();
+        for release in releases {
+            match output.get(&release.feature_version) {
+                Some(newest) if newest.version_key() >= release.version_key() => {}
+                _ => {
+                    output.insert(release.feature_version, release);
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+#[async_trait]
+impl Vendor for Liberica {
+    fn name(&self) -> &'static str {
+        match self.bundle {
+            Bundle::Jdk => "liberica",
+            Bundle::JdkFull => "liberica-full",
+        }
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let releases = self
+            .get_releases(ctx, CHANNEL_PLATFORM)
+            .await
+            .context("Failed to list liberica releases")?;
+        Channels::from_majors(
+            self.name(),
+            releases.values().map(|release| Major {
+                major: release.feature_version,
+                early_access: !release.ga,
+                lts: release.lts,
+            }),
+        )
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        Ok(self
+            .get_releases(ctx, platform)
+            .await?
+            .into_iter()
+            .map(|(major, release)| (major, release.into_package(platform.os)))
+            .collect())
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    collections::HashMap,
+    io,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use async_lock::{Semaphore, SemaphoreGuardArc};
+use async_std::io::{BufRead, Read};
+use surf::{
+    middleware::{Middleware, Next},
+    Body, Client, Request, Response,
+};
+
+/// Middleware limiting the number of requests in flight to each host
+///
+/// A request holds on to its host's permit until its response body has been dropped, so
+/// streaming downloads count against the limit for as long as they are running.
+#[derive(Debug)]
+pub struct HostLimiter {
+    limit: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl HostLimiter {
+    /// Allows up to `limit` concurrent requests per host
+    pub fn new(limit: usize) -> Self {
+        HostLimiter {
+            limit: limit.max(1),
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Gets the semaphore for a host, creating it if this is the first request to it
+    fn semaphore(&self, host: &str) -> Arc<Semaphore> {
+        let mut hosts = self.hosts.lock().expect("Host limiter lock poisoned");
+        hosts
+            .entry(host.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.limit)))
+            .clone()
+    }
+}
+
+#[surf::utils::async_trait]
+impl Middleware for HostLimiter {
+    async fn handle(&self, req: Request, client: Client, next: Next<'_>) -> surf::Result<Response> {
+        let host = req.url().host_str().unwrap_or_default().to_string();
+        let permit = self.semaphore(&host).acquire_arc().await;
+        let mut res = next.run(req, client).await?;
+        let body = res.take_body();
+        let len = body.len();
+        let mime = body.mime().clone();
+        let mut body = Body::from_reader(
+            LimitedBody {
+                body,
+                _permit: permit,
+            },
+            len,
+        );
+        body.set_mime(mime);
+        res.set_body(body);
+        Ok(res)
+    }
+}
+
+/// A response body holding on to its host's permit
+struct LimitedBody {
+    body: Body,
+    _permit: SemaphoreGuardArc,
+}
+
+impl Read for LimitedBody {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.body).poll_read(cx, buf)
+    }
+}
+
+impl BufRead for LimitedBody {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        Pin::new(&mut self.get_mut().body).poll_fill_buf(cx)
+    }
+
+    fn consume(mut self: Pin<&mut Self>, amt: usize) {
+        Pin::new(&mut self.body).consume(amt)
+    }
+}
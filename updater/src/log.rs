@@ -0,0 +1,65 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much the updater reports on stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Level {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Level::Warn as u8);
+
+impl Level {
+    /// Level for the given number of `-v` flags, or `Error` if `-q` was passed
+    pub fn from_flags(verbose: u8, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Level::Error,
+            (false, 0) => Level::Warn,
+            (false, 1) => Level::Info,
+            (false, _) => Level::Debug,
+        }
+    }
+}
+
+/// Sets the most detailed level that gets printed
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` get printed
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// Prints a warning, unless running quietly
+#[macro_export]
+macro_rules! warning {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Warn) {
+            eprintln!("warning: {}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Prints progress information with `-v`
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Info) {
+            eprintln!("{}", format_args!($($arg)*));
+        }
+    };
+}
+
+/// Prints detailed progress information with `-vv`
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)*) => {
+        if $crate::log::enabled($crate::log::Level::Debug) {
+            eprintln!("{}", format_args!($($arg)*));
+        }
+    };
+}
@@ -1,168 +1,97 @@
-use std::{collections::BTreeMap, process::Command};
+use std::process;
 
+use clap::Parser;
 use color_eyre::{
-    eyre::{eyre, Context, Result},
-    Section, SectionExt,
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use updater::{
+    changelog::{Changelog, Format},
+    context::Context,
+    info, log, output,
+    platform::Platform,
+    Selection, SourcesFile,
 };
-use serde::{Deserialize, Serialize};
-use surf::Client;
 
-/// Adoptium API
-pub mod adoptium;
-/// Semeru API
-pub mod semeru;
+/// Command line interface
+pub mod cli;
 
-/// Java release struct
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
-pub struct Release {
-    link: String,
-    major_version: u64,
-    java_version: String,
-    early_access: bool,
-    sha256: String,
-}
+use cli::{Cli, Command};
 
-/// Sources serialization struct
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
-pub struct Sources {
-    versions: BTreeMap<String, Release>,
-    latest: Release,
-    stable: Release,
-    lts: Release,
-}
-
-/// System serialization struct
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
-pub struct System {
-    temurin: Sources,
-    semeru: Sources,
-}
-
-impl TryFrom<adoptium::Release> for Release {
-    type Error = color_eyre::eyre::Report;
-
-    fn try_from(value: adoptium::Release) -> Result<Self> {
-        if value.binaries.len() == 1 {
-            let package = &value.binaries[0].package;
-            Ok(Release {
-                link: package.link.clone(),
-                major_version: value.version_data.major,
-                java_version: value.version_data.openjdk_version,
-                early_access: value.release_type == "ea",
-                sha256: get_sha256(&package.link).context("Failed to prefetch package")?,
-            })
-        } else {
-            Err(eyre!(
-                "Adoptium release had an incorrect number of binaries"
-            ))
-        }
-    }
-}
+/// Exit status of `check` when the sources file is out of date, errors exit with 1
+const EXIT_OUT_OF_DATE: i32 = 2;
 
 #[async_std::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
-    // Create a client
-    let client = Client::new();
-    // Get list of releases from adoptium, we'll use this for some other things
-    let available = adoptium::get_available_releases(&client)
-        .await
-        .context("Failed to get list of available releases")?;
-    let lts_version = available
-        .available_lts_releases
-        .iter()
-        .copied()
-        .max()
-        .expect("No LTSs?");
-    // Get adoptium releases
-    let adoptium_releases = get_adoptium_releases(&client).await?;
-    // Spit out to the serialization format
-    let temurin = Sources {
-        versions: adoptium_releases
-            .clone()
-            .into_iter()
-            .map(|(k, v)| (format!("jdk{}", k), v))
-            .collect(),
-        latest: adoptium_releases
-            .get(&available.most_recent_feature_version)
-            .expect("Missing release")
-            .clone(),
-        stable: adoptium_releases
-            .get(&available.most_recent_feature_release)
-            .expect("Missing release")
-            .clone(),
-        lts: adoptium_releases
-            .get(&lts_version)
-            .expect("Missing release")
-            .clone(),
-    };
-    // Get semeru releases
-
-    let semeru_releases = get_semeru_releases(&client).await?;
-    // Spit out to the serialization format
-    let semeru = Sources {
-        versions: semeru_releases
-            .clone()
-            .into_iter()
-            .map(|(k, v)| (format!("jdk{}", k), v))
-            .collect(),
-        latest: semeru_releases
-            .get(&available.most_recent_feature_release)
-            .expect("Missing release")
-            .clone(),
-        stable: semeru_releases
-            .get(&available.most_recent_feature_release)
-            .expect("Missing release")
-            .clone(),
-        lts: semeru_releases
-            .get(&lts_version)
-            .expect("Missing release")
-            .clone(),
-    };
-    let system = System { temurin, semeru };
-    let mut systems = BTreeMap::new();
-    systems.insert("x86_64-linux".to_string(), system);
-    let output = serde_json::to_string_pretty(&systems).context("Failed to encode sources")?;
-    println!("{}", output);
+    let cli = Cli::parse();
+    log::set_level(log::Level::from_flags(cli.verbose, cli.quiet));
+    let selection = cli.selection()?;
+    match &cli.command {
+        Command::Update { stdout } => {
+            let (_, updated) = update(&cli, &selection).await?;
+            if *stdout {
+                output::validate(&updated).context("Refusing to print invalid sources")?;
+                let output =
+                    serde_json::to_string_pretty(&updated).context("Failed to encode sources")?;
+                println!("{}", output);
+            } else {
+                updated.save(&cli.sources)?;
+                info!("Wrote {}", cli.sources.display());
+            }
+        }
+        Command::Check => {
+            let (current, updated) = update(&cli, &selection).await?;
+            let changelog = Changelog::new(&current, &updated);
+            if changelog.is_empty() {
+                println!("{} is up to date", cli.sources.display());
+            } else {
+                println!("{} is out of date\n", cli.sources.display());
+                print!("{}", changelog);
+                process::exit(EXIT_OUT_OF_DATE);
+            }
+        }
+        Command::Diff { format } => {
+            let (current, updated) = update(&cli, &selection).await?;
+            let changelog = Changelog::new(&current, &updated);
+            // Summary first, so the output can be used as is for a commit message
+            match format {
+                Format::Text => println!("{}\n", changelog.summary()),
+                Format::Markdown => println!("## {}\n", changelog.summary()),
+            }
+            print!("{}", changelog.render(*format));
+        }
+        Command::Show { vendor, version } => {
+            let platform = match selection.platforms.as_slice() {
+                [platform] => *platform,
+                _ => Platform::host()
+                    .ok_or_else(|| eyre!("Unsupported host system"))
+                    .suggestion("Pick a system with --system")?,
+            };
+            let current = SourcesFile::load(&cli.sources)?;
+            let release = current
+                .system(&platform.nix_system())
+                .and_then(|system| system.vendor(vendor))
+                .and_then(|sources| sources.get(version))
+                .ok_or_else(|| eyre!("No {} {} pinned for {}", vendor, version, platform))
+                .with_section(|| cli.sources.display().to_string().header("Sources"))?;
+            let output =
+                serde_json::to_string_pretty(release).context("Failed to encode release")?;
+            println!("{}", output);
+        }
+    }
     Ok(())
 }
 
-/// Get the releases from adoptium
-pub async fn get_adoptium_releases(client: &Client) -> Result<BTreeMap<u64, Release>> {
-    let releases: Result<BTreeMap<u64, Release>> = adoptium::get_releases(client)
-        .await?
-        .into_iter()
-        .map(|(key, val)| match val.try_into() {
-            Ok(val) => Ok((key, val)),
-            Err(err) => Err(err),
-        })
-        .collect();
-
-    releases.context("Failed getting release from adoptium")
-}
-
-/// Get the releases from semeru
-pub async fn get_semeru_releases(client: &Client) -> Result<BTreeMap<u64, Release>> {
-    let releases: Result<BTreeMap<u64, Release>> = semeru::get_releases(client)
-        .await?
-        .into_iter()
-        .map(|(key, val)| match val.try_into() {
-            Ok(val) => Ok((key, val)),
-            Err(err) => Err(err),
-        })
-        .collect();
-
-    releases.context("Failed getting release from adoptium")
-}
-
-/// Gets the nix sha256 for a url
-fn get_sha256(url: &str) -> Result<String> {
-    let output = Command::new("nix-prefetch-url")
-        .args([url, "--type", "sha256"])
-        .output()
-        .with_section(|| format!("Failed to prefetch url: {}", url).header("Prefetch Failure"))
-        .context("Failed to prefetch")?;
-    let output = String::from_utf8(output.stdout).context("Invalid utf-8 from nix pre fetch")?;
-    // Trim the trailing new line
-    Ok(output.trim().to_string())
+/// Fetches the selected vendors and systems, returning the current sources along with the
+/// current sources updated with what was fetched
+async fn update(cli: &Cli, selection: &Selection) -> Result<(SourcesFile, SourcesFile)> {
+    let current = if cli.sources.exists() {
+        SourcesFile::load(&cli.sources)?
+    } else {
+        SourcesFile::default()
+    };
+    let ctx = Context::new(cli.config())?;
+    let updated = updater::update(&ctx, &current, selection).await?;
+    Ok((current, updated))
 }
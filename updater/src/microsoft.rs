@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+
+use async_lock::OnceCell;
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use futures::future::try_join_all;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    adoptium,
+    config::{api_url, Config},
+    context::Context,
+    error::Error,
+    pager::{self, NotFound},
+    platform::Platform,
+    prefetch::PublishedChecksum,
+    retry,
+    vendor::{self, Channels, Vendor},
+};
+
+/// Name of microsoft in the marketplace api
+pub const VENDOR: &str = "microsoft";
+
+/// Response from the `/v1/info/available_releases/{vendor}` endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct AvailableReleases {
+    pub available_lts_releases: Vec<u64>,
+    pub available_releases: Vec<u64>,
+}
+
+/// Package for a particular binary
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Package {
+    pub name: String,
+    pub link: String,
+    #[serde(default)]
+    pub sha256sum: Option<String>,
+    #[serde(default)]
+    pub sha256sum_link: Option<String>,
+}
+
+/// Information about a particular binary
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Binary {
+    pub os: String,
+    pub architecture: String,
+    pub image_type: String,
+    pub package: Package,
+}
+
+/// Version data
+#[derive(Deserialize, Serialize, Debug)]
+pub struct VersionData {
+    pub major: u64,
+    pub minor: u64,
+    pub security: u64,
+    pub build: u64,
+    pub openjdk_version: String,
+}
+
+/// Response item from the `/v1/assets/latest/{vendor}/{feature_version}` endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Asset {
+    pub binary: Binary,
+    pub release_name: String,
+    pub version: VersionData,
+}
+
+impl Asset {
+    /// Converts into a package of the vendor agnostic pipeline
+    pub fn into_package(self, platform: Platform) -> vendor::Package {
+        let package = self.binary.package;
+        vendor::Package {
+            link: package.link,
+            major_version: self.version.major,
+            java_version: self.version.openjdk_version,
+            early_access: false,
+            checksum: PublishedChecksum {
+                checksum: package.sha256sum.filter(|s| !s.is_empty()),
+                checksum_link: package.sha256sum_link.filter(|s| !s.is_empty()),
+            },
+            java_home: platform.os.java_home().map(str::to_string),
+            distribution_version: None,
+        }
+    }
+}
+
+/// Asset query struct
+#[derive(Deserialize, Serialize, Debug)]
+pub struct AssetQuery {
+    pub os: String,
+    pub architecture: String,
+    pub image_type: String,
+}
+
+/// The Microsoft Build of OpenJDK, from the adoptium marketplace api
+pub struct Microsoft {
+    /// Base url of the api
+    api: String,
+    /// Releases the api lists, looked up once per run
+    available: OnceCell<AvailableReleases>,
+}
+
+impl Microsoft {
+    /// Sets up microsoft with the configured api
+    pub fn new(config: &Config) -> Self {
+        Microsoft {
+            api: config.marketplace_api.clone(),
+            available: OnceCell::new(),
+        }
+    }
+
+    /// Attempts to get the available releases
+    pub async fn get_available_releases(&self, ctx: &Context) -> Result<&AvailableReleases> {
+        self.available
+            .get_or_try_init(|| async {
+                let endpoint =
+                    api_url(&self.api, &format!("v1/info/available_releases/{}", VENDOR));
+                let mut response = retry::send(&ctx.client, ctx.client.get(&endpoint).build())
+                    .await
+                    .context("Failed to request available versions from the marketplace")?;
+                response
+                    .body_json()
+                    .await
+                    .map_err(|e| eyre!(e))
+                    .context("Failed to request available versions from the marketplace")
+                    .with_section(|| endpoint.clone().header("Failed Request:"))
+            })
+            .await
+    }
+
+    /// Attempts to get the latest tarball of a particular version
+    ///
+    /// Returns `None` if microsoft does not publish this version for the given platform
+    pub async fn get_latest(
+        &self,
+        ctx: &Context,
+        version: u64,
+        platform: Platform,
+    ) -> Result<Option<Asset>> {
+        let endpoint = api_url(
+            &self.api,
+            &format!("v1/assets/latest/{}/{}", VENDOR, version),
+        );
+        let request = ctx
+            .client
+            .get(endpoint)
+            .query(&AssetQuery {
+                os: adoptium::os_name(platform.os).to_string(),
+                architecture: platform.arch.name().to_string(),
+                image_type: "jdk".to_string(),
+            })
+            .map_err(|e| eyre!(e))
+            .context("Failed to build request")?
+            .build();
+        let query = request.url().as_str().to_string();
+        let assets: Vec<Asset> = pager::get_all(&ctx.client, request, NotFound::End)
+            .await
+            .context("Failed to get release information from the marketplace")
+            .with_section(move || query.header("Failed Request"))?;
+        // Installers are listed too, the tarball is what nix can unpack
+        Ok(assets
+            .into_iter()
+            .find(|asset| asset.binary.package.name.ends_with(".tar.gz")))
+    }
+}
+
+#[async_trait]
+impl Vendor for Microsoft {
+    fn name(&self) -> &'static str {
+        "microsoft"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let available = self.get_available_releases(ctx).await?;
+        // Microsoft only ships lts majors, so every channel tracks the newest one
+        let lts = available
+            .available_lts_releases
+            .iter()
+            .copied()
+            .max()
+            .ok_or_else(|| Error::NoRelease {
+                vendor: self.name(),
+                channel: "lts",
+                available: available.available_releases.clone(),
+            })?;
+        Ok(Channels {
+            latest: lts,
+            stable: lts,
+            lts,
+        })
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        let available = self.get_available_releases(ctx).await?;
+        let assets = try_join_all(
+            available
+                .available_releases
+                .iter()
+                .map(|&version| async move {
+                    let asset =
+                        self.get_latest(ctx, version, platform)
+                            .await
+                            .with_context(|| {
+                                format!("Failed to get version {} for {}", version, platform)
+                            })?;
+                    Ok::<_, color_eyre::eyre::Report>(
+                        asset.map(|asset| (version, asset.into_package(platform))),
+                    )
+                }),
+        )
+        .await?;
+        Ok(assets.into_iter().flatten().collect())
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use async_lock::OnceCell;
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use futures::future::try_join_all;
+use surf::StatusCode;
+
+use crate::{
+    adoptium::Adoptium,
+    config::{api_url, Config},
+    context::Context,
+    error::Error,
+    platform::{Arch, Platform},
+    prefetch::PublishedChecksum,
+    retry,
+    vendor::{self, Channels, Vendor},
+};
+
+/// Architectures oracle builds early access releases for
+const ARCHES: &[Arch] = &[Arch::X86_64, Arch::Aarch64];
+
+/// The current early access build of a major, as listed on its jdk.java.net page
+#[derive(Debug, Clone)]
+pub struct Build {
+    pub major: u64,
+    /// Version of the build, e.g. `24-ea+27`
+    pub java_version: String,
+    /// Links to the tarballs, keyed by platform, e.g. `linux-x64`
+    pub archives: HashMap<String, String>,
+}
+
+impl Build {
+    /// Picks the tarball links for a major out of its download page
+    ///
+    /// Returns `None` if the page doesn't link any, e.g. once the major has been released
+    pub fn parse(major: u64, page: &str) -> Option<Self> {
+        let prefix = format!("openjdk-{}-ea+", major);
+        let mut java_version = None;
+        let mut archives = HashMap::new();
+        for link in page
+            .split("href=\"")
+            .skip(1)
+            .filter_map(|s| s.split('"').next())
+        {
+            // e.g. openjdk-24-ea+27_linux-x64_bin.tar.gz
+            let file = link.rsplit('/').next().unwrap_or_default();
+            let (version, platform) = match file
+                .strip_suffix("_bin.tar.gz")
+                .filter(|name| name.starts_with(&prefix))
+                .and_then(|name| name.split_once('_'))
+            {
+                Some(parts) => parts,
+                None => continue,
+            };
+            java_version.get_or_insert_with(|| version["openjdk-".len()..].to_string());
+            archives.insert(platform.to_string(), link.to_string());
+        }
+        Some(Build {
+            major,
+            java_version: java_version?,
+            archives,
+        })
+    }
+
+    /// Converts the tarball for a platform into a package of the vendor agnostic pipeline
+    pub fn to_package(&self, platform: Platform) -> Option<vendor::Package> {
+        if !ARCHES.contains(&platform.arch) {
+            return None;
+        }
+        let key = format!("{}-{}", platform.os.name(), platform.arch.name());
+        let link = self.archives.get(&key)?;
+        Some(vendor::Package {
+            link: link.clone(),
+            major_version: self.major,
+            java_version: self.java_version.clone(),
+            early_access: true,
+            checksum: PublishedChecksum {
+                checksum: None,
+                checksum_link: Some(format!("{}.sha256", link)),
+            },
+            java_home: platform.os.java_home().map(str::to_string),
+            distribution_version: None,
+        })
+    }
+}
+
+/// Oracle's OpenJDK early access builds, from jdk.java.net
+///
+/// None of the builds are generally available, so `stable` follows the build closest to its
+/// release and `lts` the build of the next long term support major, or the same as `stable`
+/// when that major isn't in development yet.
+pub struct OpenJdkEa {
+    /// Base url of jdk.java.net
+    api: String,
+    /// Temurin, which knows which majors are still in development
+    adoptium: Arc<Adoptium>,
+    /// The current build of every major in development, looked up once per run
+    builds: OnceCell<BTreeMap<u64, Build>>,
+}
+
+impl OpenJdkEa {
+    /// Sets up the early access builds with the configured api, sharing temurin's view of the
+    /// majors in development
+    pub fn new(config: &Config, adoptium: Arc<Adoptium>) -> Self {
+        OpenJdkEa {
+            api: config.openjdk_ea_api.clone(),
+            adoptium,
+            builds: OnceCell::new(),
+        }
+    }
+
+    /// Attempts to get the current build of a major
+    ///
+    /// Returns `None` if jdk.java.net has no early access builds of it
+    pub async fn get_build(&self, ctx: &Context, major: u64) -> Result<Option<Build>> {
+        let endpoint = api_url(&self.api, &format!("{}/", major));
+        let mut response = retry::send(&ctx.client, ctx.client.get(&endpoint).build())
+            .await
+            .with_context(|| format!("Failed to request the jdk{} download page", major))?;
+        if response.status() == StatusCode::NotFound {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(eyre!(
+                "Download page request failed with status {}",
+                response.status()
+            ))
+            .with_section(|| endpoint.clone().header("Failed Request:"));
+        }
+        let page = response
+            .body_string()
+            .await
+            .map_err(|e| eyre!(e))
+            .with_context(|| format!("Failed to read the jdk{} download page", major))
+            .with_section(|| endpoint.clone().header("Failed Request:"))?;
+        Ok(Build::parse(major, &page))
+    }
+
+    /// Attempts to get the current build of every major newer than the latest release, up to
+    /// the tip
+    pub async fn get_builds(&self, ctx: &Context) -> Result<&BTreeMap<u64, Build>> {
+        self.builds
+            .get_or_try_init(|| async {
+                let available = self.adoptium.get_available_releases(ctx).await?;
+                let majors = available.most_recent_feature_release + 1..=available.tip_version;
+                let builds = try_join_all(majors.map(|major| self.get_build(ctx, major))).await?;
+                Ok::<_, color_eyre::eyre::Report>(
+                    builds
+                        .into_iter()
+                        .flatten()
+                        .map(|build| (build.major, build))
+                        .collect(),
+                )
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Vendor for OpenJdkEa {
+    fn name(&self) -> &'static str {
+        "openjdk-ea"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let available = self.adoptium.get_available_releases(ctx).await?;
+        let builds = self
+            .get_builds(ctx)
+            .await
+            .context("Failed to list openjdk early access builds")?;
+        let oldest = builds
+            .keys()
+            .copied()
+            .min()
+            .ok_or_else(|| Error::NoRelease {
+                vendor: self.name(),
+                channel: "stable",
+                available: builds.keys().copied().collect(),
+            })?;
+        let lts = builds
+            .keys()
+            .copied()
+            .filter(|&major| vendor::is_lts(major))
+            .max()
+            .unwrap_or(oldest);
+        Ok(Channels {
+            latest: available.tip_version,
+            stable: oldest,
+            lts,
+        })
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        Ok(self
+            .get_builds(ctx)
+            .await?
+            .iter()
+            .filter_map(|(&major, build)| Some((major, build.to_package(platform)?)))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::Os;
+
+    use super::*;
+
+    /// A trimmed copy of jdk.java.net/24 while it was in early access
+    const PAGE: &str = include_str!("../fixtures/openjdk_ea_24.html");
+
+    #[test]
+    fn parses_the_download_page() {
+        let build = Build::parse(24, PAGE).unwrap();
+        assert_eq!(build.major, 24);
+        assert_eq!(build.java_version, "24-ea+27");
+        let mut platforms: Vec<&str> = build.archives.keys().map(String::as_str).collect();
+        platforms.sort_unstable();
+        assert_eq!(
+            platforms,
+            ["linux-aarch64", "linux-x64", "macos-aarch64", "macos-x64"]
+        );
+    }
+
+    #[test]
+    fn pages_without_builds_of_the_major_have_none() {
+        assert!(Build::parse(25, PAGE).is_none());
+        assert!(Build::parse(24, "<html></html>").is_none());
+    }
+
+    #[test]
+    fn packages_link_the_published_checksum() {
+        let build = Build::parse(24, PAGE).unwrap();
+        let package = build
+            .to_package(Platform::new(Arch::Aarch64, Os::Darwin))
+            .unwrap();
+        assert_eq!(
+            package.link,
+            "https://download.java.net/java/early_access/jdk24/27/GPL/openjdk-24-ea+27_macos-aarch64_bin.tar.gz"
+        );
+        assert_eq!(
+            package.checksum.checksum_link.as_deref(),
+            Some("https://download.java.net/java/early_access/jdk24/27/GPL/openjdk-24-ea+27_macos-aarch64_bin.tar.gz.sha256")
+        );
+        assert!(package.early_access);
+        assert!(build
+            .to_package(Platform::new(Arch::Powerpc64le, Os::Linux))
+            .is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    process,
+};
+
+use color_eyre::{
+    eyre::{eyre, Context, Result},
+    Help, SectionExt,
+};
+
+use crate::{platform::Platform, SourcesFile};
+
+/// Length of a sha256 in nix base32
+const NIX_SHA256_LEN: usize = 52;
+
+/// Checks the sources are complete enough to be written out
+pub fn validate(sources: &SourcesFile) -> Result<()> {
+    if sources.is_empty() {
+        return Err(eyre!("No systems have any sources"))
+            .suggestion("Check the warnings above for why releases were omitted");
+    }
+    for (name, system) in sources.systems() {
+        if Platform::from_nix_system(name).is_none() {
+            return Err(eyre!("Unknown system: {}", name));
+        }
+        for (vendor, version, release) in system.entries() {
+            let entry = || format!("{} {} {}", name, vendor, version).header("Entry");
+            if release.link.is_empty() {
+                return Err(eyre!("Release has no link")).with_section(entry);
+            }
+            if release.java_version.is_empty() {
+                return Err(eyre!("Release has no java version")).with_section(entry);
+            }
+            if release.sha256.len() != NIX_SHA256_LEN {
+                return Err(eyre!("Release has an invalid sha256"))
+                    .with_section(entry)
+                    .with_section(|| release.sha256.clone().header("Sha256"));
+            }
+            if version.starts_with("jdk") && version != format!("jdk{}", release.major_version) {
+                return Err(eyre!("Release is filed under the wrong version"))
+                    .with_section(entry)
+                    .with_section(|| release.major_version.header("Major Version"));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Encodes and validates the sources, then atomically replaces the file at `path` with them
+pub fn write_sources(path: &Path, sources: &SourcesFile) -> Result<()> {
+    validate(sources).context("Refusing to write invalid sources")?;
+    let output = serde_json::to_string_pretty(sources).context("Failed to encode sources")?;
+    write_atomic(path, format!("{}\n", output).as_bytes())
+        .context("Failed to write sources")
+        .with_section(|| path.display().to_string().header("Path"))
+}
+
+/// Writes to a temporary file next to `path`, syncs it and renames it over `path`, so `path`
+/// only ever holds either its old or its new contents
+pub fn write_atomic(path: &Path, contents: &[u8]) -> Result<()> {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| eyre!("Path has no file name"))?
+        .to_string_lossy();
+    let temp = dir.join(format!(".{}.{}.tmp", file_name, process::id()));
+    let result: Result<()> = (|| {
+        let mut file = File::create(&temp).context("Failed to create temporary file")?;
+        file.write_all(contents)
+            .context("Failed to write temporary file")?;
+        file.sync_all().context("Failed to sync temporary file")?;
+        fs::rename(&temp, path).context("Failed to move temporary file into place")?;
+        Ok(())
+    })();
+    if result.is_err() {
+        // Best effort, the original error is more interesting than a failed cleanup
+        let _ = fs::remove_file(&temp);
+        return result.with_section(|| temp.display().to_string().header("Temporary File"));
+    }
+    // Make the rename itself durable, not supported on every platform so failures are ignored
+    if let Ok(dir) = File::open(&dir) {
+        let _ = dir.sync_all();
+    }
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use color_eyre::{
+    eyre::{eyre, Context, Result},
+    Help, SectionExt,
+};
+use serde::de::DeserializeOwned;
+use surf::{http::headers::AUTHORIZATION, Client, Request, StatusCode, Url};
+
+use crate::{redirect::same_origin, retry};
+
+/// What a 404 from a paginated endpoint means
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotFound {
+    /// The end of the results, which is how the adoptium apis report a page past the last one or
+    /// a query without any results
+    End,
+    /// A failed request, e.g. for a repository or organization that doesn't exist
+    Error,
+}
+
+/// Fetches every page of a paginated json endpoint, starting with `request`
+///
+/// Pages are followed through the `rel="next"` entry of the `Link` header, `not_found` decides
+/// what a 404 means. Headers of the first request are sent with every page, except for
+/// credentials when a page is on another origin.
+pub async fn get_all<T: DeserializeOwned>(
+    client: &Client,
+    request: Request,
+    not_found: NotFound,
+) -> Result<Vec<T>> {
+    let headers: Vec<_> = request
+        .iter()
+        .map(|(name, values)| (name.clone(), values.clone()))
+        .collect();
+    let mut output = Vec::new();
+    let mut next = Some(request);
+    while let Some(request) = next.take() {
+        let url = request.url().clone();
+        let mut response = retry::send(client, request).await?;
+        if response.status() == StatusCode::NotFound && not_found == NotFound::End {
+            // Drain the body so the connection can be reused
+            let _ = response.body_bytes().await;
+            break;
+        }
+        if !response.status().is_success() {
+            return Err(eyre!("Request failed with status {}", response.status()))
+                .with_section(|| url.to_string().header("Failed Request"));
+        }
+        let page: Vec<T> = response
+            .body_json()
+            .await
+            .map_err(|e| eyre!(e))
+            .context("Failed to decode page")
+            .with_section(|| url.to_string().header("Failed Request"))?;
+        // An empty page means there is nothing more to get, even if the server links one
+        if page.is_empty() {
+            break;
+        }
+        output.extend(page);
+        if let Some(link) = response.header("Link") {
+            next = next_link(&url, link.last().as_str()).map(|next| {
+                // Credentials only go to the host that was asked for them
+                let keep_auth = same_origin(&url, &next);
+                let mut request = client.get(next).build();
+                for (name, values) in &headers {
+                    if keep_auth || *name != AUTHORIZATION {
+                        request.insert_header(name, values);
+                    }
+                }
+                request
+            });
+        }
+    }
+    Ok(output)
+}
+
+/// Finds the `rel="next"` url in a `Link` header
+fn next_link(base: &Url, header: &str) -> Option<Url> {
+    header.split(',').find_map(|link| {
+        let mut parts = link.split(';');
+        let target = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+        let is_next = parts.any(|param| {
+            let param = param.trim().replace(' ', "");
+            param == "rel=\"next\"" || param == "rel=next"
+        });
+        if is_next {
+            base.join(target).ok()
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base() -> Url {
+        Url::parse("https://api.github.com/repos/a/b/releases?per_page=100").unwrap()
+    }
+
+    #[test]
+    fn next_link_picks_rel_next() {
+        let header = r#"<https://api.github.com/repositories/1/releases?per_page=100&page=2>; rel="next", <https://api.github.com/repositories/1/releases?per_page=100&page=5>; rel="last""#;
+        assert_eq!(
+            next_link(&base(), header).unwrap().as_str(),
+            "https://api.github.com/repositories/1/releases?per_page=100&page=2"
+        );
+    }
+
+    #[test]
+    fn next_link_resolves_relative_targets() {
+        let header = "</v3/assets/feature_releases/17/ga?page=1>; rel=next";
+        assert_eq!(
+            next_link(&base(), header).unwrap().as_str(),
+            "https://api.github.com/v3/assets/feature_releases/17/ga?page=1"
+        );
+    }
+
+    #[test]
+    fn next_link_is_none_on_the_last_page() {
+        let header = r#"<https://api.github.com/repositories/1/releases?page=4>; rel="prev", <https://api.github.com/repositories/1/releases?page=1>; rel="first""#;
+        assert_eq!(next_link(&base(), header), None);
+        assert_eq!(next_link(&base(), "garbage"), None);
+    }
+}
@@ -0,0 +1,126 @@
+use std::fmt;
+
+/// CPU architectures we produce sources for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Arch {
+    X86_64,
+    Aarch64,
+    Powerpc64le,
+    S390x,
+}
+
+impl Arch {
+    /// Name of the architecture in a nix system string
+    pub fn nix_name(self) -> &'static str {
+        match self {
+            Arch::X86_64 => "x86_64",
+            Arch::Aarch64 => "aarch64",
+            Arch::Powerpc64le => "powerpc64le",
+            Arch::S390x => "s390x",
+        }
+    }
+
+    /// Name of the architecture in most vendor apis and archive names, e.g. `x64`
+    ///
+    /// Vendors that name it differently or don't build for every architecture map it in their
+    /// own module.
+    pub fn name(self) -> &'static str {
+        match self {
+            Arch::X86_64 => "x64",
+            Arch::Aarch64 => "aarch64",
+            Arch::Powerpc64le => "ppc64le",
+            Arch::S390x => "s390x",
+        }
+    }
+}
+
+/// Operating systems we produce sources for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Os {
+    Linux,
+    Darwin,
+}
+
+impl Os {
+    /// Name of the operating system in a nix system string
+    pub fn nix_name(self) -> &'static str {
+        match self {
+            Os::Linux => "linux",
+            Os::Darwin => "darwin",
+        }
+    }
+
+    /// Name of the operating system in most vendor apis and archive names, e.g. `macos`
+    ///
+    /// Vendors that name it differently map it in their own module.
+    pub fn name(self) -> &'static str {
+        match self {
+            Os::Linux => "linux",
+            Os::Darwin => "macos",
+        }
+    }
+
+    /// Location of the JDK root inside an archive for this operating system, if it isn't the
+    /// top level directory
+    pub fn java_home(self) -> Option<&'static str> {
+        match self {
+            Os::Linux => None,
+            Os::Darwin => Some("Contents/Home"),
+        }
+    }
+}
+
+/// A nix system, e.g. `x86_64-linux`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Platform {
+    pub arch: Arch,
+    pub os: Os,
+}
+
+impl Platform {
+    /// All the systems we produce sources for
+    pub const ALL: &'static [Platform] = &[
+        Platform::new(Arch::X86_64, Os::Linux),
+        Platform::new(Arch::Aarch64, Os::Linux),
+        Platform::new(Arch::Powerpc64le, Os::Linux),
+        Platform::new(Arch::S390x, Os::Linux),
+        Platform::new(Arch::X86_64, Os::Darwin),
+        Platform::new(Arch::Aarch64, Os::Darwin),
+    ];
+
+    pub const fn new(arch: Arch, os: Os) -> Self {
+        Platform { arch, os }
+    }
+
+    /// The nix system string for this platform
+    pub fn nix_system(self) -> String {
+        self.to_string()
+    }
+
+    /// Looks up one of our platforms by its nix system string
+    pub fn from_nix_system(system: &str) -> Option<Self> {
+        Platform::ALL
+            .iter()
+            .copied()
+            .find(|platform| platform.nix_system() == system)
+    }
+
+    /// The platform the updater is running on, if it is one of ours
+    pub fn host() -> Option<Self> {
+        let arch = match std::env::consts::ARCH {
+            "powerpc64" if cfg!(target_endian = "little") => "powerpc64le",
+            arch => arch,
+        };
+        let os = match std::env::consts::OS {
+            "macos" => "darwin",
+            os => os,
+        };
+        Platform::from_nix_system(&format!("{}-{}", arch, os))
+    }
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.arch.nix_name(), self.os.nix_name())
+    }
+}
@@ -0,0 +1,245 @@
+use std::fmt;
+
+use async_std::io::ReadExt;
+use color_eyre::{
+    eyre::{eyre, Context, Result},
+    Help, SectionExt,
+};
+use sha2::{Digest, Sha256};
+use surf::Client;
+
+use crate::{
+    config::{Config, HashMode},
+    retry,
+};
+
+/// Alphabet used by nix's base32 encoding, which omits `e`, `o`, `u` and `t`
+const NIX_BASE32_ALPHABET: &[u8; 32] = b"0123456789abcdfghijklmnpqrsvwxyz";
+
+/// Size of the chunks downloads are hashed in
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// A sha256 digest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sha256Hash([u8; 32]);
+
+impl Sha256Hash {
+    /// Parses a hex encoded sha256, as published by most vendors
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let hex = hex.trim();
+        if hex.len() != 64 || !hex.is_ascii() {
+            return Err(eyre!("Invalid sha256 checksum length"))
+                .with_section(|| hex.to_string().header("Checksum"));
+        }
+        let mut bytes = [0; 32];
+        for (byte, digits) in bytes.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            // Digits are known to be ascii, so this can't split a character
+            let digits = std::str::from_utf8(digits).expect("Checked ascii");
+            *byte = u8::from_str_radix(digits, 16)
+                .context("Invalid sha256 checksum")
+                .with_section(|| hex.to_string().header("Checksum"))?;
+        }
+        Ok(Sha256Hash(bytes))
+    }
+
+    /// Encodes the hash in nix's base32 format, as produced by `nix-prefetch-url`
+    pub fn to_nix_base32(&self) -> String {
+        nix_base32(&self.0)
+    }
+
+    /// Encodes the hash as lowercase hex
+    pub fn to_hex(&self) -> String {
+        self.0.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+
+    /// Encodes the hash as an SRI hash, e.g. `sha256-...`
+    pub fn to_sri(&self) -> String {
+        format!("sha256-{}", base64::encode(self.0))
+    }
+}
+
+impl fmt::Display for Sha256Hash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_nix_base32())
+    }
+}
+
+/// Encodes bytes in nix's base32 format
+///
+/// Nix reads the input as one little endian number and emits its 5 bit groups from the most
+/// significant end, so this isn't compatible with RFC 4648 base32.
+pub fn nix_base32(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return String::new();
+    }
+    let len = (bytes.len() * 8 - 1) / 5 + 1;
+    (0..len)
+        .rev()
+        .map(|n| {
+            let bit = n * 5;
+            let byte = bit / 8;
+            let offset = bit % 8;
+            let low = u16::from(bytes[byte]) >> offset;
+            let high = bytes
+                .get(byte + 1)
+                .map_or(0, |&next| u16::from(next) << (8 - offset));
+            NIX_BASE32_ALPHABET[usize::from((low | high) & 0x1f)] as char
+        })
+        .collect()
+}
+
+/// Downloads a url and computes the sha256 of its contents, without unpacking it
+pub async fn prefetch(client: &Client, url: &str) -> Result<Sha256Hash> {
+    let mut response = retry::send(client, client.get(url).build())
+        .await
+        .context("Failed to request download")?;
+    if !response.status().is_success() {
+        return Err(eyre!("Download failed with status {}", response.status()))
+            .with_section(|| url.to_string().header("Failed Request"));
+    }
+    let mut body = response.take_body();
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; CHUNK_SIZE];
+    loop {
+        let read = body
+            .read(&mut buffer)
+            .await
+            .context("Failed to read download")
+            .with_section(|| url.to_string().header("Failed Request"))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(Sha256Hash(hasher.finalize().into()))
+}
+
+/// Checksums a vendor published for a package
+#[derive(Debug, Clone, Default)]
+pub struct PublishedChecksum {
+    /// Hex encoded sha256 reported by the vendor's api
+    pub checksum: Option<String>,
+    /// Link to a checksum file in `sha256sum` format
+    pub checksum_link: Option<String>,
+}
+
+/// Fetches a checksum file in `sha256sum` format and parses the checksum out of it
+pub async fn fetch_checksum_file(client: &Client, url: &str) -> Result<Sha256Hash> {
+    let mut response = retry::send(client, client.get(url).build())
+        .await
+        .context("Failed to request checksum file")?;
+    if !response.status().is_success() {
+        return Err(eyre!(
+            "Checksum file request failed with status {}",
+            response.status()
+        ))
+        .with_section(|| url.to_string().header("Failed Request"));
+    }
+    let contents = response
+        .body_string()
+        .await
+        .map_err(|e| eyre!(e))
+        .context("Failed to read checksum file")
+        .with_section(|| url.to_string().header("Failed Request"))?;
+    // The checksum is followed by the file name, if there is one
+    let checksum = contents.split_whitespace().next().unwrap_or_default();
+    Sha256Hash::from_hex(checksum)
+        .context("Invalid checksum file")
+        .with_section(|| url.to_string().header("Checksum File"))
+}
+
+/// Determines the sha256 of a package according to the configured [`HashMode`]
+///
+/// Packages without any published checksum are always downloaded. In [`HashMode::Verify`] the
+/// download, the api checksum and the checksum file must all agree.
+pub async fn hash_package(
+    client: &Client,
+    config: &Config,
+    url: &str,
+    published: &PublishedChecksum,
+) -> Result<Sha256Hash> {
+    let checksum = published
+        .checksum
+        .as_deref()
+        .map(Sha256Hash::from_hex)
+        .transpose()
+        .context("Vendor published an invalid checksum")
+        .with_section(|| url.to_string().header("Package"))?;
+    match config.hash_mode {
+        HashMode::Checksum => match (checksum, &published.checksum_link) {
+            (Some(checksum), _) => Ok(checksum),
+            (None, Some(link)) => fetch_checksum_file(client, link).await,
+            (None, None) => prefetch(client, url).await,
+        },
+        HashMode::Prefetch => prefetch(client, url).await,
+        HashMode::Verify => {
+            let file = match &published.checksum_link {
+                Some(link) => Some(fetch_checksum_file(client, link).await?),
+                None => None,
+            };
+            let computed = prefetch(client, url).await?;
+            let matches = |hash: Option<Sha256Hash>| hash.is_none_or(|hash| hash == computed);
+            if matches(checksum) && matches(file) {
+                Ok(computed)
+            } else {
+                let show = |hash: Option<Sha256Hash>| {
+                    hash.map_or_else(|| "Not published".to_string(), |hash| hash.to_hex())
+                };
+                Err(eyre!(
+                    "Downloaded package does not match the published checksums"
+                ))
+                .with_section(|| url.to_string().header("Package"))
+                .with_section(|| {
+                    let link = published.checksum_link.as_deref();
+                    link.unwrap_or("Not published")
+                        .to_string()
+                        .header("Checksum Link")
+                })
+                .with_section(|| show(checksum).header("Api Checksum"))
+                .with_section(|| show(file).header("Checksum File"))
+                .with_section(|| computed.to_hex().header("Computed"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// sha256 of the empty string
+    const EMPTY_SHA256: &str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+    #[test]
+    fn nix_base32_matches_nix() {
+        // As printed by `nix-hash --type sha256 --to-base32` for the hash of an empty file
+        let hash = Sha256Hash::from_hex(EMPTY_SHA256).unwrap();
+        assert_eq!(
+            hash.to_nix_base32(),
+            "0mdqa9w1p6cmli6976v4wi0sw9r4p5prkj7lzfd1877wk11c9c73"
+        );
+    }
+
+    #[test]
+    fn nix_base32_emits_most_significant_groups_first() {
+        assert_eq!(nix_base32(&[]), "");
+        assert_eq!(nix_base32(&[0x1f]), "0z");
+        assert_eq!(nix_base32(&[0x20]), "10");
+    }
+
+    #[test]
+    fn sha256_hash_encodings() {
+        let hash = Sha256Hash::from_hex(&format!(" {}\n", EMPTY_SHA256)).unwrap();
+        assert_eq!(hash.to_hex(), EMPTY_SHA256);
+        assert_eq!(
+            hash.to_sri(),
+            "sha256-47DEQpj8HBSa+/TImW+5JCeuQeRkm5NMpJWZG3hSuFU="
+        );
+    }
+
+    #[test]
+    fn from_hex_rejects_malformed_checksums() {
+        assert!(Sha256Hash::from_hex("abc").is_err());
+        assert!(Sha256Hash::from_hex(&EMPTY_SHA256.replace('e', "g")).is_err());
+    }
+}
@@ -0,0 +1,103 @@
+use surf::{
+    http::{
+        self,
+        headers::{AUTHORIZATION, LOCATION},
+    },
+    middleware::{Middleware, Next},
+    Client, Request, Response, StatusCode, Url,
+};
+
+use crate::debug;
+
+/// Maximum number of redirects followed for a single request
+const MAX_REDIRECTS: u8 = 10;
+
+/// Middleware following redirects
+///
+/// Unlike surf's `Redirect`, every hop goes through the rest of the middleware chain, and the
+/// body of each redirect response is read before moving on, so its pooled connection is left in
+/// a usable state. Credentials are dropped when a redirect leaves the origin of the request,
+/// so a github token isn't handed to whichever host a download redirects to.
+#[derive(Debug, Default)]
+pub struct FollowRedirects;
+
+#[surf::utils::async_trait]
+impl Middleware for FollowRedirects {
+    async fn handle(
+        &self,
+        mut req: Request,
+        client: Client,
+        next: Next<'_>,
+    ) -> surf::Result<Response> {
+        for _ in 0..MAX_REDIRECTS {
+            let mut res = next.run(req.clone(), client.clone()).await?;
+            let location = match res.header(LOCATION) {
+                Some(location) if is_redirect(res.status()) => location.last().as_str().to_string(),
+                _ => return Ok(res),
+            };
+            res.body_bytes().await?;
+            let url = req.url().join(&location)?;
+            debug!("{} redirects to {}", req.url(), url);
+            if !same_origin(req.url(), &url) {
+                req.remove_header(AUTHORIZATION);
+            }
+            let http_req: &mut http::Request = req.as_mut();
+            *http_req.url_mut() = url;
+        }
+        next.run(req, client).await
+    }
+}
+
+/// Whether two urls share a scheme, host and port, so credentials for one are fine to send to
+/// the other
+pub fn same_origin(a: &Url, b: &Url) -> bool {
+    a.scheme() == b.scheme()
+        && a.host_str() == b.host_str()
+        && a.port_or_known_default() == b.port_or_known_default()
+}
+
+/// Whether a status is a redirect we follow
+fn is_redirect(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::MovedPermanently
+            | StatusCode::Found
+            | StatusCode::SeeOther
+            | StatusCode::TemporaryRedirect
+            | StatusCode::PermanentRedirect
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> Url {
+        Url::parse(url).unwrap()
+    }
+
+    #[test]
+    fn same_origin_ignores_path_and_default_port() {
+        assert!(same_origin(
+            &url("https://api.github.com/repos/a/b/releases"),
+            &url("https://api.github.com:443/repositories/1/releases?page=2"),
+        ));
+    }
+
+    #[test]
+    fn same_origin_rejects_other_hosts_schemes_and_ports() {
+        let api = url("https://api.github.com/repos/a/b/releases");
+        assert!(!same_origin(
+            &api,
+            &url("https://objects.githubusercontent.com/a")
+        ));
+        assert!(!same_origin(
+            &api,
+            &url("http://api.github.com/repos/a/b/releases")
+        ));
+        assert!(!same_origin(
+            &api,
+            &url("https://api.github.com:8443/repos/a/b/releases")
+        ));
+    }
+}
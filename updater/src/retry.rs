@@ -0,0 +1,89 @@
+use std::time::{Duration, SystemTime};
+
+use async_std::task;
+use color_eyre::{
+    eyre::{eyre, Result},
+    Help, SectionExt,
+};
+use surf::{http::other::RetryAfter, Client, Request, Response, StatusCode};
+
+use crate::debug;
+
+/// Maximum number of times a request is sent, including the first attempt
+pub const MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry, doubled for every following one
+const BASE_DELAY: Duration = Duration::from_millis(500);
+/// Longest we'll back off for on our own
+const MAX_DELAY: Duration = Duration::from_secs(30);
+/// Longest we'll wait when a server asks us to with `Retry-After`
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(120);
+
+/// Sends a request, retrying transport errors, 429s and 5xxs with jittered exponential backoff
+///
+/// `Retry-After` is honoured when the server sends it. If every attempt fails, the returned
+/// report lists each of them.
+pub async fn send(client: &Client, request: Request) -> Result<Response> {
+    let url = request.url().to_string();
+    debug!("{} {}", request.method(), url);
+    let mut attempts = Vec::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let (failure, retry_after) = match client.send(request.clone()).await {
+            Ok(response) if !is_retryable(response.status()) => return Ok(response),
+            Ok(mut response) => {
+                // Drain the body so the connection can be reused
+                let _ = response.body_bytes().await;
+                (
+                    format!("Status {}", response.status()),
+                    retry_after(&response),
+                )
+            }
+            Err(e) => (e.to_string(), None),
+        };
+        if attempt == MAX_ATTEMPTS {
+            attempts.push(format!("Attempt {}: {}", attempt, failure));
+            break;
+        }
+        let delay = retry_after.unwrap_or_else(|| backoff(attempt));
+        debug!(
+            "{} failed: {}, retrying in {:.1}s",
+            url,
+            failure,
+            delay.as_secs_f64()
+        );
+        attempts.push(format!(
+            "Attempt {}: {}, retrying in {:.1}s",
+            attempt,
+            failure,
+            delay.as_secs_f64()
+        ));
+        task::sleep(delay).await;
+    }
+    Err(eyre!("Request failed after {} attempts", MAX_ATTEMPTS))
+        .with_section(|| url.header("Failed Request"))
+        .with_section(|| attempts.join("\n").header("Attempts"))
+}
+
+/// Whether a response status is worth retrying
+fn is_retryable(status: StatusCode) -> bool {
+    status == StatusCode::TooManyRequests || status.is_server_error()
+}
+
+/// How long the server asked us to wait, if it did
+fn retry_after(response: &Response) -> Option<Duration> {
+    let retry_after = RetryAfter::from_headers(response).ok()??;
+    // A date in the past means we can go right away
+    let wait = retry_after
+        .duration_since(SystemTime::now())
+        .unwrap_or_default();
+    Some(wait.min(MAX_RETRY_AFTER))
+}
+
+/// Backoff before retrying after the given attempt, picked at random from the upper half of the
+/// exponential ceiling
+fn backoff(attempt: u32) -> Duration {
+    let ceiling = BASE_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt - 1))
+        .min(MAX_DELAY);
+    let millis = ceiling.as_millis() as u64;
+    Duration::from_millis(fastrand::u64(millis / 2..=millis))
+}
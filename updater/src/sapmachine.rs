@@ -0,0 +1,247 @@
+use std::collections::{BTreeMap, HashMap};
+
+use async_lock::OnceCell;
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{api_url, Config},
+    context::Context,
+    platform::{Arch, Platform},
+    prefetch::PublishedChecksum,
+    retry,
+    vendor::{self, Channels, Vendor},
+};
+
+/// Path of the release list, relative to the configured api
+pub const RELEASES_PATH: &str = "SapMachine/assets/data/sapmachine_releases.json";
+
+/// Architectures sap builds sapmachine for
+const ARCHES: &[Arch] = &[Arch::X86_64, Arch::Aarch64, Arch::Powerpc64le];
+
+/// A major listed in the release list
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Major {
+    /// The major version, e.g. `"21"`
+    pub id: String,
+    pub lts: bool,
+    pub ea: bool,
+}
+
+/// A release of a major, e.g. from the `sapmachine-21.0.2` tag
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Release {
+    pub tag: String,
+    pub ea: bool,
+    /// Links to the jdk downloads, keyed by platform, e.g. `linux-x64`
+    #[serde(default)]
+    pub jdk: HashMap<String, String>,
+}
+
+impl Release {
+    /// Version of the JDK, e.g. `21.0.2` or `23+20`
+    pub fn java_version(&self) -> &str {
+        self.tag.strip_prefix("sapmachine-").unwrap_or(&self.tag)
+    }
+
+    /// Link to the tarball for a platform, if sap builds one
+    pub fn tarball(&self, platform: Platform) -> Option<&str> {
+        if !ARCHES.contains(&platform.arch) {
+            return None;
+        }
+        let key = format!("{}-{}", platform.os.name(), platform.arch.name());
+        self.jdk
+            .get(&key)
+            .map(String::as_str)
+            .filter(|link| link.ends_with(".tar.gz"))
+    }
+
+    /// Converts the tarball for a platform into a package of the vendor agnostic pipeline
+    pub fn to_package(&self, major: u64, platform: Platform) -> Option<vendor::Package> {
+        let link = self.tarball(platform)?;
+        // Every archive is published next to a `.sha256.txt` of the same name
+        let checksum_link = link
+            .strip_suffix(".tar.gz")
+            .map(|base| format!("{}.sha256.txt", base));
+        Some(vendor::Package {
+            link: link.to_string(),
+            major_version: major,
+            java_version: self.java_version().to_string(),
+            early_access: self.ea,
+            checksum: PublishedChecksum {
+                checksum: None,
+                checksum_link,
+            },
+            java_home: platform.os.java_home().map(str::to_string),
+            distribution_version: None,
+        })
+    }
+}
+
+/// Releases of a major, newest first
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Asset {
+    pub releases: Vec<Release>,
+}
+
+/// Response from the published release list
+#[derive(Deserialize, Serialize, Debug)]
+pub struct ReleaseList {
+    pub majors: Vec<Major>,
+    pub assets: HashMap<String, Asset>,
+}
+
+impl ReleaseList {
+    /// Picks the newest tarball of every major for a platform
+    ///
+    /// Prefers the newest generally available build, early access builds are only used for
+    /// majors that haven't been released yet.
+    pub fn packages(&self, platform: Platform) -> BTreeMap<u64, vendor::Package> {
+        self.assets
+            .iter()
+            .filter_map(|(major, asset)| {
+                let major = major.parse().ok()?;
+                let built = || {
+                    asset
+                        .releases
+                        .iter()
+                        .filter(|release| release.tarball(platform).is_some())
+                };
+                let release = built()
+                    .find(|release| !release.ea)
+                    .or_else(|| built().next())?;
+                Some((major, release.to_package(major, platform)?))
+            })
+            .collect()
+    }
+}
+
+/// SAP SapMachine, from its published release list
+pub struct SapMachine {
+    /// Base url the release list is published under
+    api: String,
+    /// The release list, looked up once per run
+    list: OnceCell<ReleaseList>,
+}
+
+impl SapMachine {
+    /// Sets up sapmachine with the configured api
+    pub fn new(config: &Config) -> Self {
+        SapMachine {
+            api: config.sapmachine_api.clone(),
+            list: OnceCell::new(),
+        }
+    }
+
+    /// Attempts to get the release list
+    pub async fn get_release_list(&self, ctx: &Context) -> Result<&ReleaseList> {
+        self.list
+            .get_or_try_init(|| async {
+                let endpoint = api_url(&self.api, RELEASES_PATH);
+                let mut response = retry::send(&ctx.client, ctx.client.get(&endpoint).build())
+                    .await
+                    .context("Failed to request the sapmachine release list")?;
+                response
+                    .body_json()
+                    .await
+                    .map_err(|e| eyre!(e))
+                    .context("Failed to decode the sapmachine release list")
+                    .with_section(|| endpoint.clone().header("Failed Request:"))
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Vendor for SapMachine {
+    fn name(&self) -> &'static str {
+        "sapmachine"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let list = self
+            .get_release_list(ctx)
+            .await
+            .context("Failed to list sapmachine releases")?;
+        Channels::from_majors(
+            self.name(),
+            list.majors.iter().filter_map(|major| {
+                Some(vendor::Major {
+                    major: major.id.parse().ok()?,
+                    early_access: major.ea,
+                    lts: major.lts,
+                })
+            }),
+        )
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        Ok(self.get_release_list(ctx).await?.packages(platform))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::Os;
+
+    use super::*;
+
+    /// A trimmed copy of the published release list
+    fn list() -> ReleaseList {
+        serde_json::from_str(include_str!("../fixtures/sapmachine_releases.json")).unwrap()
+    }
+
+    fn versions(platform: Platform) -> Vec<(u64, String, bool)> {
+        list()
+            .packages(platform)
+            .into_iter()
+            .map(|(major, package)| (major, package.java_version, package.early_access))
+            .collect()
+    }
+
+    #[test]
+    fn prefers_the_newest_generally_available_build() {
+        assert_eq!(
+            versions(Platform::new(Arch::X86_64, Os::Linux)),
+            [
+                (17, "17.0.12".to_string(), false),
+                (21, "21.0.4".to_string(), false),
+                // A newer early access build doesn't replace the release
+                (22, "22.0.1".to_string(), false),
+                // Unreleased majors use their newest early access build
+                (23, "23+20".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_releases_without_a_tarball_for_the_platform() {
+        let versions = versions(Platform::new(Arch::Aarch64, Os::Darwin));
+        assert_eq!(versions[1], (21, "21.0.3".to_string(), false));
+        assert!(list()
+            .packages(Platform::new(Arch::S390x, Os::Linux))
+            .is_empty());
+    }
+
+    #[test]
+    fn packages_link_the_published_checksum_file() {
+        let package = &list().packages(Platform::new(Arch::Powerpc64le, Os::Linux))[&21];
+        assert_eq!(
+            package.link,
+            "https://github.com/SAP/SapMachine/releases/download/sapmachine-21.0.4/sapmachine-jdk-21.0.4_linux-ppc64le_bin.tar.gz"
+        );
+        assert_eq!(
+            package.checksum.checksum_link.as_deref(),
+            Some("https://github.com/SAP/SapMachine/releases/download/sapmachine-21.0.4/sapmachine-jdk-21.0.4_linux-ppc64le_bin.sha256.txt")
+        );
+        assert_eq!(package.checksum.checksum, None);
+    }
+}
@@ -1,95 +1,282 @@
 use std::collections::BTreeMap;
 
-use color_eyre::{
-    eyre::{eyre, Context, Result},
-    Help, SectionExt,
+use async_lock::OnceCell;
+use async_trait::async_trait;
+use color_eyre::eyre::{Context as _, Result};
+use futures::future::try_join_all;
+
+use crate::{
+    adoptium,
+    context::Context,
+    github,
+    platform::Platform,
+    prefetch::PublishedChecksum,
+    vendor::{self, Channels, Major, Vendor},
 };
-use surf::Client;
-
-use crate::adoptium::{AvailableReleases, Release, ReleaseQuery};
-
-/// Page size
-pub const PAGE_SIZE: u64 = 10;
-
-/// Attempts to get the available releases
-pub async fn get_available_releases(client: &Client) -> Result<AvailableReleases> {
-    let endpoint = "https://api.adoptopenjdk.net/v3/info/available_releases?jvm_impl=openj9";
-    client
-        .get(endpoint)
-        .recv_json()
-        .await
-        .map_err(|e| eyre!(e))
-        .context("Failed to request available versions from semeru")
-        .with_section(|| endpoint.to_string().header("Failed Request:"))
+
+/// Organization the `semeru<major>-binaries` repositories belong to
+pub const ORG: &str = "ibmruntimes";
+
+/// Major version a repository publishes, from a name like `semeru17-binaries`
+pub fn repository_major(name: &str) -> Option<u64> {
+    name.strip_prefix("semeru")?
+        .strip_suffix("-binaries")?
+        .parse()
+        .ok()
 }
 
-/// Attempts to get the release info for a particular version
-pub async fn get_release(client: &Client, version: u64, release_type: &str) -> Result<Release> {
-    let endpoint = format!(
-        "https://api.adoptopenjdk.net/v3/assets/feature_releases/{}/{}",
-        version, release_type
-    );
-    let request = client
-        .get(endpoint)
-        .query(&ReleaseQuery {
-            architecture: "x64".to_string(),
-            heap_size: "normal".to_string(),
-            image_type: "jdk".to_string(),
-            os: "linux".to_string(),
-            page_size: PAGE_SIZE,
-            project: "jdk".to_string(),
-            jvm_impl: "openj9".to_string(),
+/// A release of semeru, from a tag like `jdk-17.0.8+7_openj9-0.40.0` or
+/// `jdk8u382-b05_openj9-0.40.0`
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub major: u64,
+    /// Version of the JDK, e.g. `17.0.8+7` or `1.8.0_382-b05`
+    pub java_version: String,
+    /// Version of the OpenJ9 vm the JDK is built with, e.g. `0.40.0`
+    pub openj9_version: Option<String>,
+    pub release: github::Release,
+}
+
+impl Release {
+    /// Picks out generally available releases out of some github releases
+    pub fn from_github(release: github::Release) -> Option<Self> {
+        if release.prerelease {
+            return None;
+        }
+        let (jdk, openj9_version) = match release.tag_name.split_once("_openj9-") {
+            Some((jdk, openj9)) => (jdk, Some(openj9.to_string())),
+            None => (release.tag_name.as_str(), None),
+        };
+        let (major, java_version) = match jdk.strip_prefix("jdk-") {
+            Some(version) => (
+                version.split(['.', '+']).next()?.parse().ok()?,
+                version.to_string(),
+            ),
+            // 8 is tagged like `jdk8u382-b05`, while `java -version` reports `1.8.0_382-b05`
+            None => {
+                let (update, build) = jdk.strip_prefix("jdk8u")?.split_once("-b")?;
+                (8, format!("1.8.0_{}-b{}", update, build))
+            }
+        };
+        Some(Release {
+            major,
+            java_version,
+            openj9_version,
+            release,
         })
-        .map_err(|e| eyre!(e))
-        .context("Failed to build request")?
-        .build();
-    let query = request.url().as_str().to_string();
-    let mut releases: Vec<Release> = client
-        .recv_json(request)
-        .await
-        .map_err(|e| eyre!(e))
-        .context("Failed to get release information from semeru")
-        .with_section(move || query.header("Failed Request"))?;
-    releases.sort();
-    match releases.pop() {
-        Some(release) => Ok(release),
-        None => Err(eyre!("Semeru endpoint did not return any valid releases")),
     }
+
+    /// The jdk tarball for a platform, if the release has one
+    pub fn archive(&self, platform: Platform) -> Option<&github::Asset> {
+        let prefix = format!(
+            "ibm-semeru-open-jdk_{}_{}_",
+            platform.arch.name(),
+            adoptium::os_name(platform.os)
+        );
+        self.release
+            .assets
+            .iter()
+            .find(|asset| asset.name.starts_with(&prefix) && asset.name.ends_with(".tar.gz"))
+    }
+
+    /// Converts the tarball for a platform into a package of the vendor agnostic pipeline
+    pub fn to_package(&self, platform: Platform) -> Option<vendor::Package> {
+        let asset = self.archive(platform)?;
+        Some(vendor::Package {
+            link: asset.browser_download_url.clone(),
+            major_version: self.major,
+            java_version: self.java_version.clone(),
+            early_access: false,
+            checksum: PublishedChecksum {
+                checksum: asset.sha256(),
+                checksum_link: self
+                    .release
+                    .asset(&format!("{}.sha256.txt", asset.name))
+                    .map(|asset| asset.browser_download_url.clone()),
+            },
+            java_home: platform.os.java_home().map(str::to_string),
+            distribution_version: self.openj9_version.clone(),
+        })
+    }
+}
+
+/// IBM Semeru, from the github releases of its `semeru<major>-binaries` repositories
+pub struct Semeru {
+    /// Releases of every major, newest first, looked up once per run
+    releases: OnceCell<BTreeMap<u64, Vec<Release>>>,
 }
 
-/// Attempts to get all the versions
-pub async fn get_releases(client: &Client) -> Result<BTreeMap<u64, Release>> {
-    let available = get_available_releases(client)
-        .await
-        .context("Failed to list semeru releases")?;
-    let mut output = BTreeMap::new();
-    // Get the generally available version of all the available releases
-    for version in available.available_releases {
-        let release = get_release(client, version, "ga").await.with_context(|| {
-            format!("Failed to get version {} from the semeru archive", version)
-        })?;
-        output.insert(version, release);
+impl Default for Semeru {
+    fn default() -> Self {
+        Semeru {
+            releases: OnceCell::new(),
+        }
     }
-    // See if we already have the latest version
-    if output.contains_key(&available.most_recent_feature_version) {
-        // Go ahead and return
-        Ok(output)
-    } else {
-        let version = available.most_recent_feature_version;
-        // Otherwise try to get an EA version of it
-
-        match get_release(client, version, "ea").await {
-            Ok(release) => {
-                output.insert(version, release);
-            }
-            Err(e) => {
-                eprintln!(
-                    "Failed to get version {} (latest) from the semeru archive: {:?}",
-                    version, e
+}
+
+impl Semeru {
+    /// Attempts to get the releases of every major that has any
+    pub async fn get_releases(&self, ctx: &Context) -> Result<&BTreeMap<u64, Vec<Release>>> {
+        self.releases
+            .get_or_try_init(|| async {
+                let repositories = github::get_repositories(ctx, ORG)
+                    .await
+                    .context("Failed to list semeru repositories")?;
+                let majors = repositories
+                    .iter()
+                    .filter_map(|repository| repository_major(&repository.name));
+                let releases = try_join_all(majors.map(|major| async move {
+                    let repo = format!("{}/semeru{}-binaries", ORG, major);
+                    let releases = github::get_releases(ctx, &repo).await?;
+                    Ok::<_, color_eyre::eyre::Report>((
+                        major,
+                        releases
+                            .into_iter()
+                            .filter_map(Release::from_github)
+                            .filter(|release| release.major == major)
+                            .collect::<Vec<_>>(),
+                    ))
+                }))
+                .await?;
+                Ok::<_, color_eyre::eyre::Report>(
+                    releases
+                        .into_iter()
+                        .filter(|(_, releases)| !releases.is_empty())
+                        .collect(),
                 )
-            }
+            })
+            .await
+    }
+}
+
+#[async_trait]
+impl Vendor for Semeru {
+    fn name(&self) -> &'static str {
+        "semeru"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let releases = self.get_releases(ctx).await?;
+        // Prereleases are left out, so every major listed is generally available
+        Channels::from_majors(
+            self.name(),
+            releases.keys().map(|&major| Major {
+                major,
+                early_access: false,
+                lts: vendor::is_lts(major),
+            }),
+        )
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        // Github lists releases newest first, so the first with a tarball is the newest one
+        Ok(self
+            .get_releases(ctx)
+            .await?
+            .iter()
+            .filter_map(|(&major, releases)| {
+                let package = releases
+                    .iter()
+                    .find_map(|release| release.to_package(platform))?;
+                Some((major, package))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::platform::{Arch, Os};
+
+    use super::*;
+
+    fn github_release(tag_name: &str, prerelease: bool, assets: &[&str]) -> github::Release {
+        github::Release {
+            tag_name: tag_name.to_string(),
+            name: None,
+            draft: false,
+            prerelease,
+            assets: assets
+                .iter()
+                .map(|name| github::Asset {
+                    name: name.to_string(),
+                    browser_download_url: format!("https://github.com/{}", name),
+                    digest: Some("sha256:abc".to_string()),
+                })
+                .collect(),
         }
+    }
+
+    #[test]
+    fn parses_release_tags() {
+        let release =
+            Release::from_github(github_release("jdk-17.0.8+7_openj9-0.40.0", false, &[])).unwrap();
+        assert_eq!(release.major, 17);
+        assert_eq!(release.java_version, "17.0.8+7");
+        assert_eq!(release.openj9_version.as_deref(), Some("0.40.0"));
+
+        let release =
+            Release::from_github(github_release("jdk-21+35_openj9-0.41.0", false, &[])).unwrap();
+        assert_eq!(release.major, 21);
+        assert_eq!(release.java_version, "21+35");
+    }
+
+    #[test]
+    fn reports_8_versions_like_java_does() {
+        let release =
+            Release::from_github(github_release("jdk8u382-b05_openj9-0.40.0", false, &[])).unwrap();
+        assert_eq!(release.major, 8);
+        assert_eq!(release.java_version, "1.8.0_382-b05");
+        assert_eq!(release.openj9_version.as_deref(), Some("0.40.0"));
+    }
+
+    #[test]
+    fn skips_prereleases_and_unknown_tags() {
+        assert!(
+            Release::from_github(github_release("jdk-22+36_openj9-0.44.0", true, &[])).is_none()
+        );
+        assert!(Release::from_github(github_release("nightly", false, &[])).is_none());
+    }
+
+    #[test]
+    fn picks_the_tarball_and_checksum_of_a_platform() {
+        let release = Release::from_github(github_release(
+            "jdk-17.0.8+7_openj9-0.40.0",
+            false,
+            &[
+                "ibm-semeru-open-jdk_x64_linux_17.0.8_7_openj9-0.40.0.tar.gz",
+                "ibm-semeru-open-jdk_x64_linux_17.0.8_7_openj9-0.40.0.tar.gz.sha256.txt",
+                "ibm-semeru-open-jre_x64_linux_17.0.8_7_openj9-0.40.0.tar.gz",
+                "ibm-semeru-open-jdk_aarch64_mac_17.0.8_7_openj9-0.40.0.pkg",
+            ],
+        ))
+        .unwrap();
+        let package = release
+            .to_package(Platform::new(Arch::X86_64, Os::Linux))
+            .unwrap();
+        assert_eq!(
+            package.link,
+            "https://github.com/ibm-semeru-open-jdk_x64_linux_17.0.8_7_openj9-0.40.0.tar.gz"
+        );
+        assert_eq!(package.checksum.checksum.as_deref(), Some("abc"));
+        assert_eq!(
+            package.checksum.checksum_link.as_deref(),
+            Some("https://github.com/ibm-semeru-open-jdk_x64_linux_17.0.8_7_openj9-0.40.0.tar.gz.sha256.txt")
+        );
+        assert_eq!(package.distribution_version.as_deref(), Some("0.40.0"));
+        assert!(release
+            .to_package(Platform::new(Arch::Aarch64, Os::Darwin))
+            .is_none());
+    }
 
-        Ok(output)
+    #[test]
+    fn reads_majors_from_repository_names() {
+        assert_eq!(repository_major("semeru17-binaries"), Some(17));
+        assert_eq!(repository_major("semeru-17-binaries"), None);
+        assert_eq!(repository_major("openj9"), None);
     }
 }
@@ -0,0 +1,455 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use async_trait::async_trait;
+use color_eyre::eyre::{Context as _, Result};
+use futures::future::try_join_all;
+
+use crate::{
+    adoptium::Adoptium,
+    config::Config,
+    context::Context,
+    corretto::Corretto,
+    error::Error,
+    graalvm::GraalVm,
+    info,
+    liberica::{Bundle, Liberica},
+    microsoft::Microsoft,
+    openjdk_ea::OpenJdkEa,
+    platform::{Arch, Os, Platform},
+    prefetch::PublishedChecksum,
+    sapmachine::SapMachine,
+    semeru::Semeru,
+    warning,
+    zulu::Zulu,
+    Release, Sources,
+};
+
+/// Names of all the vendors the updater knows about, in the order they are fetched
+pub const NAMES: &[&str] = &[
+    "temurin",
+    "semeru",
+    "zulu",
+    "corretto",
+    "liberica",
+    "liberica-full",
+    "graalvm",
+    "microsoft",
+    "sapmachine",
+    "openjdk-ea",
+];
+
+/// Sets up every vendor in `names`, skipping unknown ones
+pub fn by_names(names: &[&str], config: &Config) -> Vec<Box<dyn Vendor>> {
+    // Temurin and the early access builds both need adoptium's available releases, which are
+    // only looked up once when they share it
+    let adoptium = Arc::new(Adoptium::new(config));
+    names
+        .iter()
+        .filter_map(|&name| -> Option<Box<dyn Vendor>> {
+            match name {
+                "temurin" => Some(Box::new(Arc::clone(&adoptium))),
+                "semeru" => Some(Box::new(Semeru::default())),
+                "zulu" => Some(Box::new(Zulu::new(config))),
+                "corretto" => Some(Box::new(Corretto::new(config))),
+                "liberica" => Some(Box::new(Liberica::new(config, Bundle::Jdk))),
+                "liberica-full" => Some(Box::new(Liberica::new(config, Bundle::JdkFull))),
+                "graalvm" => Some(Box::new(GraalVm::default())),
+                "microsoft" => Some(Box::new(Microsoft::new(config))),
+                "sapmachine" => Some(Box::new(SapMachine::new(config))),
+                "openjdk-ea" => Some(Box::new(OpenJdkEa::new(config, Arc::clone(&adoptium)))),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Platform whose releases decide which majors the channels track, for vendors that only list
+/// their releases per platform
+pub const CHANNEL_PLATFORM: Platform = Platform::new(Arch::X86_64, Os::Linux);
+
+/// What a vendor's metadata says about one of its majors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Major {
+    pub major: u64,
+    pub early_access: bool,
+    /// Whether the major gets long term support
+    pub lts: bool,
+}
+
+/// Majors each channel of a vendor tracks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Channels {
+    pub latest: u64,
+    pub stable: u64,
+    pub lts: u64,
+}
+
+impl Channels {
+    /// Points `latest` at the newest major, `stable` at the newest generally available one and
+    /// `lts` at the newest generally available long term support one
+    ///
+    /// Channels without a matching major fall back like [`Channels::resolve`] does.
+    pub fn from_majors(
+        vendor: &'static str,
+        majors: impl IntoIterator<Item = Major>,
+    ) -> Result<Self> {
+        let majors: Vec<Major> = majors.into_iter().collect();
+        let newest = |filter: &dyn Fn(&Major) -> bool| {
+            majors
+                .iter()
+                .filter(|major| filter(major))
+                .map(|major| major.major)
+                .max()
+        };
+        let latest = newest(&|_| true).ok_or_else(|| Error::NoRelease {
+            vendor,
+            channel: "latest",
+            available: majors.iter().map(|major| major.major).collect(),
+        })?;
+        Ok(Channels::resolve(
+            vendor,
+            latest,
+            newest(&|major| !major.early_access),
+            newest(&|major| !major.early_access && major.lts),
+        ))
+    }
+
+    /// Builds the channels out of the major a vendor's metadata names for each
+    ///
+    /// Like [`Channels::fallback`], `lts` settles for `stable` and `stable` for `latest` with a
+    /// warning.
+    pub fn resolve(vendor: &str, latest: u64, stable: Option<u64>, lts: Option<u64>) -> Self {
+        let or_fallback = |channel: &str, major: Option<u64>, fallback: (&str, u64)| {
+            major.unwrap_or_else(|| {
+                warning!(
+                    "{} lists no release for {}, it falls back to {} (jdk{})",
+                    vendor,
+                    channel,
+                    fallback.0,
+                    fallback.1
+                );
+                fallback.1
+            })
+        };
+        let stable = or_fallback("stable", stable, ("latest", latest));
+        let lts = or_fallback("lts", lts, ("stable", stable));
+        Channels {
+            latest,
+            stable,
+            lts,
+        }
+    }
+
+    /// The major of every channel, by its name in the sources file
+    pub fn named(&self) -> [(&'static str, u64); 3] {
+        [
+            ("latest", self.latest),
+            ("stable", self.stable),
+            ("lts", self.lts),
+        ]
+    }
+
+    /// Points channels whose major isn't among `releases` at the closest one that is
+    ///
+    /// `latest` falls back to the newest major, `stable` to the newest generally available one
+    /// and `lts` to the newest generally available long term support one, each settling for
+    /// the fallback before it when there is nothing better.
+    pub fn fallback(self, releases: &BTreeMap<u64, Release>) -> Self {
+        let newest = |filter: &dyn Fn(u64, &Release) -> bool| {
+            releases
+                .iter()
+                .rev()
+                .find(|(&major, release)| filter(major, release))
+                .map(|(&major, _)| major)
+        };
+        let latest = newest(&|_, _| true);
+        let stable = newest(&|_, release| !release.early_access).or(latest);
+        let lts = newest(&|major, release| !release.early_access && is_lts(major)).or(stable);
+        let pick = |preferred: u64, fallback: Option<u64>| {
+            if releases.contains_key(&preferred) {
+                preferred
+            } else {
+                fallback.unwrap_or(preferred)
+            }
+        };
+        Channels {
+            latest: pick(self.latest, latest),
+            stable: pick(self.stable, stable),
+            lts: pick(self.lts, lts),
+        }
+    }
+}
+
+/// Whether a major is a long term support release, following the openjdk lts cadence
+///
+/// For vendors whose release metadata doesn't record it
+pub fn is_lts(major: u64) -> bool {
+    major == 8 || major == 11 || (major >= 17 && (major - 17).is_multiple_of(4))
+}
+
+/// Formats the numbers of a version like `java -version` does, leaving out trailing zeros
+///
+/// e.g. `[17, 0, 8, 1]` as `17.0.8.1` and `[21, 0, 0]` as `21`
+pub fn format_version(version: &[u64]) -> String {
+    let len = version
+        .iter()
+        .rposition(|&part| part != 0)
+        .map_or(1, |i| i + 1)
+        .min(version.len());
+    version[..len]
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Formats a version of 8 like `java -version` does, e.g. `1.8.0_382-b05` or `1.8.0_402-ea-b01`
+pub fn format_jdk8_version(update: u64, early_access: bool, build: Option<u64>) -> String {
+    let mut version = format!("1.8.0_{}", update);
+    if early_access {
+        version.push_str("-ea");
+    }
+    if let Some(build) = build {
+        version.push_str(&format!("-b{:02}", build));
+    }
+    version
+}
+
+/// A package published by a vendor, before it has been hashed
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub link: String,
+    pub major_version: u64,
+    pub java_version: String,
+    pub early_access: bool,
+    /// Checksums the vendor published for the package, if any
+    pub checksum: PublishedChecksum,
+    /// Path of the JDK root inside the archive, when it isn't the top level directory
+    pub java_home: Option<String>,
+    /// Version of the distribution, when it is versioned separately from the JDK
+    pub distribution_version: Option<String>,
+}
+
+/// A JDK distribution
+#[async_trait]
+pub trait Vendor: Send + Sync {
+    /// Name of the vendor in the sources file
+    fn name(&self) -> &'static str;
+
+    /// Looks up which majors the channels currently track
+    async fn channels(&self, ctx: &Context) -> Result<Channels>;
+
+    /// Gets the newest package of every major published for a platform
+    ///
+    /// Majors that are not published for the platform are omitted
+    async fn packages(&self, ctx: &Context, platform: Platform) -> Result<BTreeMap<u64, Package>>;
+}
+
+/// Lets vendors that share state with another one be set up behind an [`Arc`]
+#[async_trait]
+impl<T: Vendor + ?Sized> Vendor for Arc<T> {
+    fn name(&self) -> &'static str {
+        (**self).name()
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        (**self).channels(ctx).await
+    }
+
+    async fn packages(&self, ctx: &Context, platform: Platform) -> Result<BTreeMap<u64, Package>> {
+        (**self).packages(ctx, platform).await
+    }
+}
+
+/// Fetches and hashes everything a vendor publishes for a platform
+///
+/// Channels whose major isn't published for the platform fall back to another major with a
+/// warning, see [`Channels::fallback`]. Returns `None` if the vendor publishes nothing for it.
+pub async fn fetch_sources(
+    ctx: &Context,
+    vendor: &dyn Vendor,
+    channels: Channels,
+    platform: Platform,
+) -> Result<Option<Sources>> {
+    let packages = vendor
+        .packages(ctx, platform)
+        .await
+        .with_context(|| format!("Failed to get {} releases for {}", vendor.name(), platform))?;
+    if packages.is_empty() {
+        info!("{} publishes nothing for {}", vendor.name(), platform);
+        return Ok(None);
+    }
+    let releases = try_join_all(packages.into_iter().map(|(major, package)| async move {
+        let release = Release::from_package(ctx, package)
+            .await
+            .with_context(|| format!("Failed to hash {} jdk{}", vendor.name(), major))?;
+        Ok::<_, color_eyre::eyre::Report>((major, release))
+    }))
+    .await?;
+    let releases: BTreeMap<u64, Release> = releases.into_iter().collect();
+    let resolved = channels.fallback(&releases);
+    for ((channel, preferred), (_, major)) in channels.named().into_iter().zip(resolved.named()) {
+        if preferred != major {
+            warning!(
+                "{} publishes no jdk{} for {}, {} falls back to jdk{}",
+                vendor.name(),
+                preferred,
+                platform,
+                channel,
+                major
+            );
+        }
+    }
+    Ok(Sources::new(releases, resolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Releases of the given majors, with the early access ones marked
+    fn releases(majors: &[u64], early_access: &[u64]) -> BTreeMap<u64, Release> {
+        majors
+            .iter()
+            .map(|&major| {
+                let release = Release {
+                    major_version: major,
+                    early_access: early_access.contains(&major),
+                    ..Release::default()
+                };
+                (major, release)
+            })
+            .collect()
+    }
+
+    fn major(major: u64, early_access: bool, lts: bool) -> Major {
+        Major {
+            major,
+            early_access,
+            lts,
+        }
+    }
+
+    #[test]
+    fn follows_the_openjdk_lts_cadence() {
+        let lts: Vec<u64> = (8..=30).filter(|&major| is_lts(major)).collect();
+        assert_eq!(lts, [8, 11, 17, 21, 25, 29]);
+    }
+
+    #[test]
+    fn formats_versions_without_trailing_zeros() {
+        assert_eq!(format_version(&[17, 0, 8, 1]), "17.0.8.1");
+        assert_eq!(format_version(&[17, 0, 8]), "17.0.8");
+        assert_eq!(format_version(&[21, 0, 0]), "21");
+        assert_eq!(format_version(&[0, 0]), "0");
+    }
+
+    #[test]
+    fn formats_8_like_java_does() {
+        assert_eq!(format_jdk8_version(382, false, Some(5)), "1.8.0_382-b05");
+        assert_eq!(format_jdk8_version(402, true, Some(1)), "1.8.0_402-ea-b01");
+        assert_eq!(format_jdk8_version(412, false, None), "1.8.0_412");
+    }
+
+    #[test]
+    fn channels_track_the_newest_matching_major() {
+        let channels = Channels::from_majors(
+            "test",
+            [
+                major(11, false, true),
+                major(17, false, true),
+                major(22, false, false),
+                major(23, true, false),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            channels,
+            Channels {
+                latest: 23,
+                stable: 22,
+                lts: 17,
+            }
+        );
+    }
+
+    #[test]
+    fn channels_without_a_major_fall_back_like_fallback_does() {
+        // Nothing long term supported, lts settles for stable
+        assert_eq!(
+            Channels::from_majors("test", [major(22, false, false), major(23, true, false)])
+                .unwrap(),
+            Channels {
+                latest: 23,
+                stable: 22,
+                lts: 22,
+            }
+        );
+        // Nothing generally available, stable and lts settle for latest
+        assert_eq!(
+            Channels::from_majors("test", [major(23, true, false)]).unwrap(),
+            Channels {
+                latest: 23,
+                stable: 23,
+                lts: 23,
+            }
+        );
+        let error = Channels::from_majors("test", []).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::NoRelease {
+                vendor: "test",
+                channel: "latest",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn published_channels_are_kept() {
+        let channels = Channels {
+            latest: 23,
+            stable: 22,
+            lts: 21,
+        };
+        assert_eq!(
+            channels.fallback(&releases(&[17, 21, 22, 23], &[23])),
+            channels
+        );
+    }
+
+    #[test]
+    fn missing_channels_fall_back_to_the_closest_published_major() {
+        let channels = Channels {
+            latest: 23,
+            stable: 22,
+            lts: 21,
+        };
+        assert_eq!(
+            channels.fallback(&releases(&[11, 17, 20], &[20])),
+            Channels {
+                latest: 20,
+                stable: 17,
+                lts: 17,
+            }
+        );
+        // Without anything generally available, every channel settles for early access
+        assert_eq!(
+            channels.fallback(&releases(&[24], &[24])),
+            Channels {
+                latest: 24,
+                stable: 24,
+                lts: 24,
+            }
+        );
+        // Without any long term support release, lts settles for stable
+        assert_eq!(
+            channels.fallback(&releases(&[20, 22], &[])),
+            Channels {
+                latest: 22,
+                stable: 22,
+                lts: 22,
+            }
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+
+use async_trait::async_trait;
+use color_eyre::{
+    eyre::{eyre, Context as _, Result},
+    Help, SectionExt,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    config::{api_url, Config},
+    context::Context,
+    pager::{self, NotFound},
+    platform::{Arch, Os, Platform},
+    prefetch::PublishedChecksum,
+    vendor::{self, Channels, Major, Vendor, CHANNEL_PLATFORM},
+};
+
+/// Page size, large enough that the latest packages of every major fit in one page
+pub const PAGE_SIZE: u64 = 1000;
+
+/// Architectures azul builds zulu for
+const ARCHES: &[Arch] = &[Arch::X86_64, Arch::Aarch64];
+
+/// Package from the `/metadata/v1/zulu/packages/` endpoint
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Package {
+    package_uuid: String,
+    name: String,
+    pub java_version: Vec<u64>,
+    pub openjdk_build_number: Option<u64>,
+    pub download_url: String,
+    pub distro_version: Vec<u64>,
+    /// `ga` or `ea`
+    #[serde(default)]
+    pub release_status: String,
+    /// `lts`, `mts` or `sts`
+    #[serde(default)]
+    pub support_term: String,
+    /// Hex encoded sha256 of the package
+    pub sha256_hash: Option<String>,
+}
+
+impl Package {
+    /// Major version of the package
+    pub fn major(&self) -> u64 {
+        self.java_version.first().copied().unwrap_or_default()
+    }
+
+    /// Whether this is an early access build
+    pub fn early_access(&self) -> bool {
+        self.release_status == "ea"
+    }
+
+    /// The openjdk version string as `java -version` reports it, e.g. `17.0.8.1+1`, `22-ea+20`
+    /// or `1.8.0_382-b05`
+    pub fn openjdk_version(&self) -> String {
+        if let [8, _, update, ..] = self.java_version[..] {
+            return vendor::format_jdk8_version(
+                update,
+                self.early_access(),
+                self.openjdk_build_number,
+            );
+        }
+        let mut version = vendor::format_version(&self.java_version);
+        if self.early_access() {
+            version.push_str("-ea");
+        }
+        if let Some(build) = self.openjdk_build_number {
+            version.push_str(&format!("+{}", build));
+        }
+        version
+    }
+
+    /// Converts into a package of the vendor agnostic pipeline
+    pub fn into_package(self) -> vendor::Package {
+        vendor::Package {
+            major_version: self.major(),
+            java_version: self.openjdk_version(),
+            early_access: self.early_access(),
+            link: self.download_url,
+            checksum: PublishedChecksum {
+                checksum: self.sha256_hash,
+                checksum_link: None,
+            },
+            // The archives link the JDK root at the top level already
+            java_home: None,
+            distribution_version: None,
+        }
+    }
+}
+
+/// Package query struct
+#[derive(Deserialize, Serialize, Debug)]
+pub struct PackageQuery {
+    pub os: String,
+    pub arch: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lib_c_type: Option<String>,
+    pub archive_type: String,
+    pub java_package_type: String,
+    pub javafx_bundled: bool,
+    pub crac_supported: bool,
+    pub latest: bool,
+    pub release_status: String,
+    pub availability_types: String,
+    pub certifications: String,
+    pub include_fields: String,
+    pub page: u64,
+    pub page_size: u64,
+}
+
+/// Azul Zulu, from the azul metadata api
+pub struct Zulu {
+    /// Base url of the api
+    api: String,
+}
+
+impl Zulu {
+    /// Sets up zulu with the configured api
+    pub fn new(config: &Config) -> Self {
+        Zulu {
+            api: config.azul_api.clone(),
+        }
+    }
+
+    /// Attempts to get the latest package of each major for a platform
+    ///
+    /// Returns nothing if azul does not build zulu for the platform
+    pub async fn get_latest_packages(
+        &self,
+        ctx: &Context,
+        release_status: &str,
+        platform: Platform,
+    ) -> Result<Vec<Package>> {
+        if !ARCHES.contains(&platform.arch) {
+            return Ok(Vec::new());
+        }
+        let endpoint = api_url(&self.api, "metadata/v1/zulu/packages/");
+        let request = ctx
+            .client
+            .get(endpoint)
+            .query(&PackageQuery {
+                os: platform.os.name().to_string(),
+                arch: platform.arch.name().to_string(),
+                lib_c_type: (platform.os == Os::Linux).then(|| "glibc".to_string()),
+                archive_type: "tar.gz".to_string(),
+                java_package_type: "jdk".to_string(),
+                javafx_bundled: false,
+                crac_supported: false,
+                latest: true,
+                release_status: release_status.to_string(),
+                availability_types: "CA".to_string(),
+                certifications: "tck".to_string(),
+                include_fields: "release_status,support_term,sha256_hash".to_string(),
+                page: 1,
+                page_size: PAGE_SIZE,
+            })
+            .map_err(|e| eyre!(e))
+            .context("Failed to build request")?
+            .build();
+        let query = request.url().as_str().to_string();
+        pager::get_all(&ctx.client, request, NotFound::Error)
+            .await
+            .context("Failed to get package information from azul")
+            .with_section(move || query.header("Failed Request"))
+    }
+
+    /// Attempts to get the newest package of every major for a platform
+    ///
+    /// Early access builds are only included for majors without a general availability release
+    pub async fn get_packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, Package>> {
+        let (ga, ea) = futures::try_join!(
+            self.get_latest_packages(ctx, "ga", platform),
+            self.get_latest_packages(ctx, "ea", platform),
+        )?;
+        let mut output = newest_by_major(ga);
+        for (major, package) in newest_by_major(ea) {
+            output.entry(major).or_insert(package);
+        }
+        Ok(output)
+    }
+}
+
+/// Keeps the newest package of each major
+fn newest_by_major(packages: Vec<Package>) -> BTreeMap<u64, Package> {
+    let mut output: BTreeMap<u64, Package> = BTreeMap::new();
+    for package in packages {
+        match output.get(&package.major()) {
+            Some(newest) if newest.distro_version >= package.distro_version => {}
+            _ => {
+                output.insert(package.major(), package);
+            }
+        }
+    }
+    output
+}
+
+#[async_trait]
+impl Vendor for Zulu {
+    fn name(&self) -> &'static str {
+        "zulu"
+    }
+
+    async fn channels(&self, ctx: &Context) -> Result<Channels> {
+        let packages = self
+            .get_packages(ctx, CHANNEL_PLATFORM)
+            .await
+            .context("Failed to list zulu releases")?;
+        Channels::from_majors(
+            self.name(),
+            packages.values().map(|package| Major {
+                major: package.major(),
+                early_access: package.early_access(),
+                lts: package.support_term == "lts",
+            }),
+        )
+    }
+
+    async fn packages(
+        &self,
+        ctx: &Context,
+        platform: Platform,
+    ) -> Result<BTreeMap<u64, vendor::Package>> {
+        Ok(self
+            .get_packages(ctx, platform)
+            .await?
+            .into_iter()
+            .map(|(major, package)| (major, package.into_package()))
+            .collect())
+    }
+}